@@ -0,0 +1,23 @@
+extern crate cc;
+
+use std::env;
+use std::process::Command;
+
+fn main() {
+    // llvm-sys finds llvm-config for us and passes its path on through `links = "llvm"`.
+    let config = env::var("DEP_LLVM_CONFIG_PATH").unwrap_or_else(|_| "llvm-config".to_owned());
+    let output = Command::new(&config)
+        .arg("--cxxflags")
+        .output()
+        .expect("failed to run llvm-config");
+    let cxxflags = String::from_utf8(output.stdout).expect("llvm-config output isn't UTF-8");
+
+    let mut build = cc::Build::new();
+    build.cpp(true).file("wrappers/llvm_rs.cpp").warnings(false);
+    // Warning and optimization flags from LLVM's own build may not suit this compiler.
+    for flag in cxxflags.split_whitespace().filter(|flag| !flag.starts_with("-W")) {
+        build.flag(flag);
+    }
+    build.compile("llvm_rs_wrappers");
+    println!("cargo:rerun-if-changed=wrappers/llvm_rs.cpp");
+}
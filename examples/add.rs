@@ -16,7 +16,10 @@ fn main() {
     let value = builder.build_add(a, b);
     builder.build_ret(value);
     module.verify().unwrap();
-    let ee = JitEngine::new(&module, JitOptions { opt_level: 3 }).unwrap();
+    let ee = JitEngine::new(&module, JitOptions {
+        opt_level: 3,
+        ..JitOptions::default()
+    }).unwrap();
     ee.with_function(func, |add: extern "C" fn((f64, f64)) -> f64| {
         println!("{} + {} = {}", 1., 2., add((1., 2.)));
     });
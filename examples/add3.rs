@@ -18,7 +18,10 @@ fn main() {
     let value = builder.build_add(value, c);
     builder.build_ret(value);
     module.verify().unwrap();
-    let ee = JitEngine::new(&module, JitOptions { opt_level: 3 }).unwrap();
+    let ee = JitEngine::new(&module, JitOptions {
+        opt_level: 3,
+        ..JitOptions::default()
+    }).unwrap();
     ee.with_function(func, |add: extern "C" fn((f64, f64, f64)) -> f64| {
         println!("{} + {} + {} = {}", 1., 2., 3., add((1., 2., 3.)));
     });
@@ -21,7 +21,7 @@ fn main() {
     builder.build_ret(three_r);
     module.verify().unwrap();
 
-    let ee = llvm::JitEngine::new(&module, llvm::JitOptions { opt_level: 0 }).unwrap();
+    let ee = llvm::JitEngine::new(&module, llvm::JitOptions::default()).unwrap();
     println!("{:?}", module);
     ee.with_function(func, |thr: T| {
         for i in 0..3 {
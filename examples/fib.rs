@@ -42,7 +42,7 @@ fn main() {
         .expect("Couldn't write to file");
 
     module.verify().unwrap();
-    let ee = JitEngine::new(&module, JitOptions { opt_level: 0 }).unwrap();
+    let ee = JitEngine::new(&module, JitOptions::default()).unwrap();
     ee.with_function(func, |fib: extern "C" fn(u64) -> u64| {
         for i in 0..10 {
             println!("fib {} = {}", i, fib(i))
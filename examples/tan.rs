@@ -8,8 +8,9 @@ extern "C" {}
 fn main() {
     let ctx = Context::new();
     let module = Module::new("simple", &ctx);
-    let cos = module.add_function("llvm.cos.f64", Type::get::<fn(f64) -> f64>(&ctx));
-    let sin = module.add_function("llvm.sin.f64", Type::get::<fn(f64) -> f64>(&ctx));
+    let f64_t = Type::get::<f64>(&ctx);
+    let cos = Intrinsic::Cos.declare(&module, &[f64_t]);
+    let sin = Intrinsic::Sin.declare(&module, &[f64_t]);
     let func = module.add_function("tan", Type::get::<fn(f64) -> f64>(&ctx));
     let entry = func.append("entry");
     let builder = Builder::new(&ctx);
@@ -20,7 +21,7 @@ fn main() {
     let value = builder.build_div(sin_v, cos_v);
     builder.build_ret(value);
     module.verify().unwrap();
-    let ee = JitEngine::new(&module, JitOptions { opt_level: 0 }).unwrap();
+    let ee = JitEngine::new(&module, JitOptions::default()).unwrap();
 
     ee.with_function(func, |tan: extern "C" fn(f64) -> f64| {
         for i in 0..10 {
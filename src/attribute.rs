@@ -0,0 +1,227 @@
+use libc::{c_char, c_uint, size_t};
+use ffi::prelude::LLVMAttributeRef;
+use ffi::{core, LLVMAttributeFunctionIndex, LLVMAttributeIndex, LLVMAttributeReturnIndex};
+use std::marker::PhantomData;
+use std::str::{self, Utf8Error};
+use std::sync::OnceLock;
+use std::{fmt, ptr, slice};
+use context::Context;
+use ffi_ext;
+use types::Type;
+
+/// An attribute that gives LLVM extra information about a function, its return value or
+/// one of its parameters.
+///
+/// There are three kinds of attributes:
+///
+/// * Enum attributes, like `noinline` or `nonnull`, which are identified by their name and
+///   may carry an integer, like `align` or `dereferenceable`.
+/// * Type attributes, like `sret` or `byval`, which carry a type.
+/// * String attributes, which are arbitrary key-value pairs.
+pub struct Attribute(PhantomData<[u8]>);
+native_ref!(&Attribute = LLVMAttributeRef);
+impl Attribute {
+    /// Create a new enum attribute with the kind name given, like `noinline` or `noalias`.
+    ///
+    /// This will panic if LLVM doesn't know an attribute with that name.
+    pub fn new_enum<'a>(context: &'a Context, kind: &str) -> &'a Attribute {
+        unsafe { core::LLVMCreateEnumAttribute(context.into(), get_kind_id(kind), 0) }.into()
+    }
+    /// Create a new enum attribute that carries an integer, like `align` or `dereferenceable`.
+    ///
+    /// This will panic if `kind` isn't the name of an attribute that carries an integer.
+    pub fn new_int<'a>(context: &'a Context, kind: &str, value: u64) -> &'a Attribute {
+        assert!(INT_KIND_NAMES.contains(&kind), "{} does not carry an integer", kind);
+        unsafe { core::LLVMCreateEnumAttribute(context.into(), get_kind_id(kind), value) }.into()
+    }
+    /// Create a new attribute that carries a type, like `sret` or `byval`.
+    ///
+    /// This will panic if LLVM doesn't know an attribute with that name.
+    pub fn new_type<'a>(context: &'a Context, kind: &str, ty: &'a Type) -> &'a Attribute {
+        unsafe { ffi_ext::LLVMCreateTypeAttribute(context.into(), get_kind_id(kind), ty.into()) }
+            .into()
+    }
+    /// Create a new string attribute with the key and value given.
+    pub fn new_string<'a>(context: &'a Context, key: &str, value: &str) -> &'a Attribute {
+        unsafe {
+            core::LLVMCreateStringAttribute(
+                context.into(),
+                key.as_ptr() as *const c_char,
+                key.len() as c_uint,
+                value.as_ptr() as *const c_char,
+                value.len() as c_uint,
+            )
+        }.into()
+    }
+    /// Returns true if this is an enum attribute.
+    pub fn is_enum(&self) -> bool {
+        unsafe { core::LLVMIsEnumAttribute(self.into()) != 0 }
+    }
+    /// Returns true if this is a type attribute.
+    pub fn is_type(&self) -> bool {
+        unsafe { ffi_ext::LLVMIsTypeAttribute(self.into()) != 0 }
+    }
+    /// Returns true if this is a string attribute.
+    pub fn is_string(&self) -> bool {
+        unsafe { core::LLVMIsStringAttribute(self.into()) != 0 }
+    }
+    /// Returns true if this is an enum or type attribute with the kind name given.
+    pub fn is_kind(&self, kind: &str) -> bool {
+        !self.is_string()
+            && unsafe { core::LLVMGetEnumAttributeKind(self.into()) } == get_kind_id(kind)
+    }
+    /// Returns the integer this enum attribute carries, or 0 if it doesn't carry one.
+    pub fn get_int(&self) -> u64 {
+        unsafe { core::LLVMGetEnumAttributeValue(self.into()) }
+    }
+    /// Returns the type this type attribute carries, or `None` if this isn't a type attribute.
+    pub fn get_type(&self) -> Option<&Type> {
+        if self.is_type() {
+            Some(unsafe { ffi_ext::LLVMGetTypeAttributeValue(self.into()) }.into())
+        } else {
+            None
+        }
+    }
+    /// Returns the key of this string attribute, or `None` if this isn't a string attribute.
+    ///
+    /// Attributes parsed from IR can have any bytes in their key, so this returns an error if
+    /// the key isn't valid UTF-8.
+    pub fn get_string_key(&self) -> Option<Result<&str, Utf8Error>> {
+        self.get_string_bytes().map(|(key, _)| str::from_utf8(key))
+    }
+    /// Returns the value of this string attribute, or `None` if this isn't a string attribute.
+    ///
+    /// Attributes parsed from IR can have any bytes in their value, so this returns an error if
+    /// the value isn't valid UTF-8.
+    pub fn get_string_value(&self) -> Option<Result<&str, Utf8Error>> {
+        self.get_string_bytes().map(|(_, value)| str::from_utf8(value))
+    }
+    /// Returns the key and value of this string attribute, or `None` if this isn't a string
+    /// attribute.
+    fn get_string_bytes(&self) -> Option<(&[u8], &[u8])> {
+        if self.is_string() {
+            unsafe {
+                let (mut key_len, mut value_len) = (0, 0);
+                let key = core::LLVMGetStringAttributeKind(self.into(), &mut key_len);
+                let value = core::LLVMGetStringAttributeValue(self.into(), &mut value_len);
+                Some((to_bytes(key, key_len), to_bytes(value, value_len)))
+            }
+        } else {
+            None
+        }
+    }
+}
+impl fmt::Debug for Attribute {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if let Some((key, value)) = self.get_string_bytes() {
+            let (key, value) = (String::from_utf8_lossy(key), String::from_utf8_lossy(value));
+            write!(fmt, "\"{}\"=\"{}\"", key, value)
+        } else {
+            let id = unsafe { core::LLVMGetEnumAttributeKind(self.into()) };
+            let kind = get_kind_name(id);
+            if let Some(ty) = self.get_type() {
+                write!(fmt, "{}({:?})", kind, ty)
+            } else if INT_KIND_NAMES.contains(&kind) {
+                write!(fmt, "{}({})", kind, self.get_int())
+            } else {
+                write!(fmt, "{}", kind)
+            }
+        }
+    }
+}
+
+/// The place an attribute is attached to on a function or call site.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AttributeIndex {
+    /// The return value.
+    Return,
+    /// The function itself.
+    Function,
+    /// The parameter with the index given, starting from 0.
+    Param(usize),
+}
+impl From<AttributeIndex> for LLVMAttributeIndex {
+    fn from(index: AttributeIndex) -> LLVMAttributeIndex {
+        match index {
+            AttributeIndex::Return => LLVMAttributeReturnIndex,
+            AttributeIndex::Function => LLVMAttributeFunctionIndex,
+            AttributeIndex::Param(index) => index as c_uint + 1,
+        }
+    }
+}
+
+/// The names of the enum attribute kinds that carry an integer.
+const INT_KIND_NAMES: &[&str] = &[
+    "align",
+    "alignstack",
+    "allocsize",
+    "dereferenceable",
+    "dereferenceable_or_null",
+    "vscale_range",
+];
+
+/// The names of the other enum and type attribute kinds, which LLVM can't look up by ID.
+///
+/// Names this version of LLVM doesn't know are skipped by `get_kind_name`.
+const KIND_NAMES: &[&str] = &[
+    "alwaysinline", "argmemonly", "builtin", "byref", "byval", "cold", "convergent",
+    "disable_sanitizer_instrumentation", "elementtype", "hot", "immarg", "inaccessiblememonly",
+    "inaccessiblemem_or_argmemonly", "inalloca", "inlinehint", "inreg", "jumptable", "minsize",
+    "mustprogress", "naked", "nest", "noalias", "nobuiltin", "nocallback", "nocapture",
+    "nocf_check", "noduplicate", "nofree", "noimplicitfloat", "noinline", "nomerge",
+    "nonlazybind", "nonnull", "noprofile", "noredzone", "noreturn", "nosanitize_coverage",
+    "nosync", "noundef", "nounwind", "null_pointer_is_valid", "optforfuzzing", "optnone",
+    "optsize", "preallocated", "readnone", "readonly", "returned", "returns_twice", "safestack",
+    "sanitize_address", "sanitize_hwaddress", "sanitize_memory", "sanitize_memtag",
+    "sanitize_thread", "shadowcallstack", "signext", "speculatable",
+    "speculative_load_hardening", "sret", "ssp", "sspreq", "sspstrong", "strictfp",
+    "swiftasync", "swifterror", "swiftself", "uwtable", "willreturn", "writeonly", "zeroext",
+];
+
+/// Returns the kind ID of the enum attribute with the name given, or 0 if there isn't one.
+fn find_kind_id(kind: &str) -> c_uint {
+    unsafe {
+        core::LLVMGetEnumAttributeKindForName(kind.as_ptr() as *const c_char, kind.len() as size_t)
+    }
+}
+
+/// Returns the kind ID of the enum attribute with the name given.
+pub fn get_kind_id(kind: &str) -> c_uint {
+    let id = find_kind_id(kind);
+    if id == 0 {
+        panic!("unknown attribute kind {}", kind)
+    }
+    id
+}
+
+/// Returns the name of the enum attribute kind with the ID given, or `"unknown"` if it isn't
+/// one this crate knows about.
+fn get_kind_name(id: c_uint) -> &'static str {
+    static NAMES: OnceLock<Vec<&'static str>> = OnceLock::new();
+    let names = NAMES.get_or_init(|| {
+        let last = unsafe { core::LLVMGetLastEnumAttributeKind() } as usize;
+        let mut names = vec!["unknown"; last + 1];
+        for &name in INT_KIND_NAMES.iter().chain(KIND_NAMES) {
+            let id = find_kind_id(name) as usize;
+            if id != 0 && id <= last {
+                names[id] = name;
+            }
+        }
+        names
+    });
+    names.get(id as usize).cloned().unwrap_or("unknown")
+}
+
+unsafe fn to_bytes<'a>(ptr: *const c_char, len: c_uint) -> &'a [u8] {
+    slice::from_raw_parts(ptr as *const u8, len as usize)
+}
+
+/// Collect the attributes that LLVM writes out with `fill` into a vector.
+pub unsafe fn collect<'a, F>(count: c_uint, fill: F) -> Vec<&'a Attribute>
+where
+    F: FnOnce(*mut LLVMAttributeRef),
+{
+    let mut attrs: Vec<LLVMAttributeRef> = vec![ptr::null_mut(); count as usize];
+    fill(attrs.as_mut_ptr());
+    attrs.into_iter().map(|attr| attr.into()).collect()
+}
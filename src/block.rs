@@ -62,7 +62,7 @@ impl BasicBlock {
     /// Delete this basic block.
     ///
     /// This is unsafe because there should be no other reference to this, but
-    /// this can't be guranteed using Rust semantics.
+    /// this can't be guaranteed using Rust semantics.
     pub unsafe fn delete(&self) {
         core::LLVMDeleteBasicBlock(self.into())
     }
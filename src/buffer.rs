@@ -1,62 +1,108 @@
 use libc::{c_char, size_t};
 use ffi::{core, LLVMMemoryBuffer};
 use ffi::prelude::LLVMMemoryBufferRef;
-use cbox::{CBox, DisposeRef};
+use cbox::{CBox, CSemiBox, DisposeRef};
 use std::ops::Deref;
 use std::marker::PhantomData;
-use std::mem;
+use std::{mem, ptr, slice, str};
 use std::ffi::CString;
-use std::default::Default;
+use std::str::Utf8Error;
 use util;
 
+/// A read-only block of bytes, such as the contents of a bitcode or object file.
+///
+/// The buffers made by the constructors here are disposed when they are dropped, unless they
+/// are passed to a function that takes ownership of them, like `ObjectFile::from_buffer`.
 pub struct MemoryBuffer(PhantomData<[u8]>);
 native_ref!(&MemoryBuffer = LLVMMemoryBufferRef);
 impl MemoryBuffer {
-    pub fn new_from_file(path: &str) -> Result<CBox<MemoryBuffer>, CBox<str>> {
+    /// Read the file at the path given into a buffer, or return an error string.
+    pub fn new_from_file(path: &str) -> Result<CSemiBox<'static, MemoryBuffer>, CBox<str>> {
         util::with_cstr(path, |path| unsafe {
             let mut output = mem::uninitialized();
             let mut error = mem::uninitialized();
             if core::LLVMCreateMemoryBufferWithContentsOfFile(path, &mut output, &mut error) == 1 {
                 Err(CBox::new(error))
             } else {
-                Ok(CBox::new(output))
+                Ok(CSemiBox::new(output))
             }
         })
     }
 
-    pub fn new_from_str(buf: &str, name: Option<&str>) -> Result<CBox<MemoryBuffer>, CBox<str>> {
+    /// Read all of standard input into a buffer, or return an error string.
+    pub fn new_from_stdin() -> Result<CSemiBox<'static, MemoryBuffer>, CBox<str>> {
         unsafe {
-            let in_name = name.map(|n| n.as_bytes().to_vec())
-                .map(|mut v| {
-                    v.push(0);
-                    CString::from_vec_unchecked(v)
-                })
-                .unwrap_or(CString::default());
+            let mut output = ptr::null_mut();
+            let mut error = ptr::null_mut();
+            if core::LLVMCreateMemoryBufferWithSTDIN(&mut output, &mut error) == 1 {
+                Err(CBox::new(error))
+            } else {
+                Ok(CSemiBox::new(output))
+            }
+        }
+    }
+
+    /// Copy the text given into a new buffer.
+    pub fn new_from_str(
+        buf: &str,
+        name: Option<&str>,
+    ) -> Result<CSemiBox<'static, MemoryBuffer>, CBox<str>> {
+        Ok(MemoryBuffer::new_from_bytes(buf.as_bytes(), name))
+    }
 
-            let out = core::LLVMCreateMemoryBufferWithMemoryRangeCopy(
-                buf.as_ptr() as *const c_char,
-                buf.len() as size_t,
-                in_name.as_ptr(),
-            );
-            Ok(CBox::new(out))
+    /// Copy the bytes given into a new buffer.
+    pub fn new_from_bytes(bytes: &[u8], name: Option<&str>) -> CSemiBox<'static, MemoryBuffer> {
+        let name = CString::new(name.unwrap_or("")).unwrap();
+        unsafe {
+            CSemiBox::new(core::LLVMCreateMemoryBufferWithMemoryRangeCopy(
+                bytes.as_ptr() as *const c_char,
+                bytes.len() as size_t,
+                name.as_ptr(),
+            ))
         }
     }
-}
-impl Deref for MemoryBuffer {
-    type Target = str;
-    fn deref(&self) -> &str {
+
+    /// Make a buffer that refers to the bytes given without copying them.
+    pub fn new_from_slice<'a>(bytes: &'a [u8], name: Option<&str>) -> CSemiBox<'a, MemoryBuffer> {
+        let name = CString::new(name.unwrap_or("")).unwrap();
         unsafe {
-            #[allow(dead_code)]
-            struct StrSlice {
-                data: *const c_char,
-                len: usize,
-            }
-            mem::transmute(StrSlice {
-                data: core::LLVMGetBufferStart(self.into()),
-                len: core::LLVMGetBufferSize(self.into()) as usize,
-            })
+            CSemiBox::new(core::LLVMCreateMemoryBufferWithMemoryRange(
+                bytes.as_ptr() as *const c_char,
+                bytes.len() as size_t,
+                name.as_ptr(),
+                0,
+            ))
         }
     }
+
+    /// Returns the contents of this buffer.
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe {
+            let start = core::LLVMGetBufferStart(self.into()) as *const u8;
+            slice::from_raw_parts(start, self.len())
+        }
+    }
+
+    /// Returns the contents of this buffer as text, or an error if it is not valid UTF-8.
+    pub fn as_str(&self) -> Result<&str, Utf8Error> {
+        str::from_utf8(self.as_bytes())
+    }
+
+    /// Returns the size of this buffer in bytes.
+    pub fn len(&self) -> usize {
+        unsafe { core::LLVMGetBufferSize(self.into()) as usize }
+    }
+
+    /// Returns true if this buffer has no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+impl Deref for MemoryBuffer {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        self.as_bytes()
+    }
 }
 impl DisposeRef for MemoryBuffer {
     type RefTo = LLVMMemoryBuffer;
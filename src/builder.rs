@@ -1,12 +1,19 @@
 use libc::{c_char, c_uint};
 use ffi::prelude::{LLVMBuilderRef, LLVMValueRef};
-use ffi::{core, LLVMBuilder, LLVMIntPredicate, LLVMRealPredicate};
+use ffi::{core, debuginfo, LLVMBuilder};
 use cbox::CSemiBox;
 use std::marker::PhantomData;
+use std::ptr;
 use block::BasicBlock;
-use context::Context;
-use types::Type;
-use value::{Function, Predicate, Value};
+use compile::Compile;
+use context::{Context, GetContext};
+use debuginfo::DIScope;
+use ffi_ext;
+use intrinsics::Intrinsic;
+use module::Module;
+use types::{PointerType, StructType, Type};
+use util::{self, Sub};
+use value::{Function, Instruction, Predicate, Value};
 
 static NULL_NAME: [c_char; 1] = [0];
 
@@ -49,6 +56,77 @@ impl Builder {
     pub fn position_at_end(&self, block: &BasicBlock) {
         unsafe { core::LLVMPositionBuilderAtEnd(self.into(), block.into()) }
     }
+    /// Position the builder right before `instr`, so new instructions are inserted before it.
+    pub fn position_before(&self, instr: &Instruction) {
+        unsafe { core::LLVMPositionBuilderBefore(self.into(), instr.into()) }
+    }
+    /// Position the builder right before `instr` in `block`.
+    pub fn position_at(&self, block: &BasicBlock, instr: &Instruction) {
+        unsafe { core::LLVMPositionBuilder(self.into(), block.into(), instr.into()) }
+    }
+    /// Clear the builder's position, so it has to be positioned again before building.
+    pub fn clear_insertion_position(&self) {
+        unsafe { core::LLVMClearInsertionPosition(self.into()) }
+    }
+    /// Attach the source location given to the instructions built from now on.
+    ///
+    /// The builder must be positioned in a function, and `scope` is usually the function's
+    /// debug information from `DebugInfoBuilder::create_function` or a lexical block in it.
+    pub fn set_current_debug_location(&self, line: usize, column: usize, scope: &DIScope) {
+        unsafe {
+            let loc = debuginfo::LLVMDIBuilderCreateDebugLocation(
+                self.get_module().get_context().into(),
+                line as c_uint,
+                column as c_uint,
+                scope.into(),
+                ptr::null_mut(),
+            );
+            ffi_ext::LLVMSetCurrentDebugLocation2(self.into(), loc);
+        }
+    }
+    /// Stop attaching a source location to the instructions built from now on.
+    pub fn clear_current_debug_location(&self) {
+        unsafe { ffi_ext::LLVMSetCurrentDebugLocation2(self.into(), ptr::null_mut()) }
+    }
+    /// Insert `instr`, which must not be in a basic block yet, at the builder's position.
+    pub fn insert(&self, instr: &Instruction) {
+        assert!(instr.get_parent().is_none(), "{:?} is already in a block", instr);
+        unsafe { core::LLVMInsertIntoBuilder(self.into(), instr.into()) }
+    }
+    /// Insert `instr`, which must not be in a basic block yet, at the builder's position and
+    /// give it the name `name`.
+    pub fn insert_with_name(&self, instr: &Instruction, name: &str) {
+        assert!(instr.get_parent().is_none(), "{:?} is already in a block", instr);
+        util::with_cstr(name, |ptr| unsafe {
+            core::LLVMInsertIntoBuilderWithName(self.into(), instr.into(), ptr)
+        })
+    }
+    /// Build an instruction with `build`, then give the value it returns the name `name`.
+    ///
+    /// Every instruction is unnamed by default, so it shows up as `%0`, `%1` and so on in the
+    /// printed IR. This makes it easier to find when reading the IR:
+    ///
+    /// ```rust
+    /// use llvm_rs::*;
+    /// let context = Context::new();
+    /// let module = Module::new("named", &context);
+    /// let func = module.add_function("double", Type::get::<fn(u64) -> u64>(&context));
+    /// let builder = Builder::new(&context);
+    /// builder.position_at_end(func.append("entry"));
+    /// let sum = builder.build_named("sum", |b| b.build_add(&func[0], &func[0]));
+    /// assert_eq!(sum.get_name(), Some("sum"));
+    /// ```
+    ///
+    /// This will panic if the value is void, because void values can't have names.
+    pub fn build_named<'a, F>(&'a self, name: &str, build: F) -> &'a Value
+    where
+        F: FnOnce(&'a Builder) -> &'a Value,
+    {
+        let value = build(self);
+        assert!(!value.get_type().is_void(), "cannot name void value {:?}", value);
+        value.set_name(name);
+        value
+    }
     /// Build an instruction that returns from the function with void.
     pub fn build_ret_void(&self) -> &Value {
         unsafe { core::LLVMBuildRetVoid(self.into()) }.into()
@@ -70,24 +148,146 @@ impl Builder {
             )
         }.into()
     }
-    /// Build an instruction that allocates a pointer to fit the size of `ty` then returns this pointer.
+    /// Build an instruction that allocates space for a value of type `ty` in the current
+    /// function's stack frame, then returns a pointer to it.
     ///
-    /// Make sure to call `build_free` with the pointer value when you're done with it, or you're
-    /// gonna have a bad time.
+    /// This memory is released automatically when the function returns, so the pointer must
+    /// never be passed to `build_free`.
     pub fn build_alloca(&self, ty: &Type) -> &Value {
         unsafe {
             core::LLVMBuildAlloca(self.into(), ty.into(), NULL_NAME.as_ptr() as *const c_char)
         }.into()
     }
-    /// Build an instruction that frees the `val`, which _MUST_ be a pointer that was returned
-    /// from `build_alloca`.
+    /// Build a call to `malloc` that allocates a value of type `ty` on the heap, then returns
+    /// a pointer to it.
+    ///
+    /// Make sure to call `build_free` with the pointer value when you're done with it, or you're
+    /// gonna have a bad time.
+    pub fn build_malloc(&self, ty: &Type) -> &Value {
+        unsafe {
+            core::LLVMBuildMalloc(self.into(), ty.into(), NULL_NAME.as_ptr() as *const c_char)
+        }.into()
+    }
+    /// Build a call to `malloc` that allocates an array with the element type `elem` and the
+    /// size `size` on the heap, then returns a pointer to it.
+    ///
+    /// Make sure to call `build_free` with the pointer value when you're done with it.
+    pub fn build_array_malloc(&self, elem: &Type, size: &Value) -> &Value {
+        unsafe {
+            core::LLVMBuildArrayMalloc(
+                self.into(),
+                elem.into(),
+                size.into(),
+                NULL_NAME.as_ptr() as *const c_char,
+            )
+        }.into()
+    }
+    /// Build a call to `free` that releases `val`, which _MUST_ be a pointer that was returned
+    /// from `build_malloc` or `build_array_malloc`.
     pub fn build_free(&self, val: &Value) -> &Value {
         unsafe { core::LLVMBuildFree(self.into(), val.into()) }.into()
     }
+    /// Build a call to the `llvm.memcpy` intrinsic that copies `len` bytes from `src` to `dest`.
+    ///
+    /// Both pointers are assumed to be aligned to `align` bytes, and the memory they point to
+    /// must not overlap.
+    pub fn build_memcpy(
+        &self,
+        dest: &Value,
+        src: &Value,
+        len: &Value,
+        align: usize,
+        volatile: bool,
+    ) -> &Value {
+        self.build_mem_transfer(Intrinsic::MemCpy, dest, src, len, align, volatile)
+    }
+    /// Build a call to the `llvm.memmove` intrinsic that copies `len` bytes from `src` to `dest`.
+    ///
+    /// Unlike `build_memcpy`, the memory the two pointers point to may overlap.
+    pub fn build_memmove(
+        &self,
+        dest: &Value,
+        src: &Value,
+        len: &Value,
+        align: usize,
+        volatile: bool,
+    ) -> &Value {
+        self.build_mem_transfer(Intrinsic::MemMove, dest, src, len, align, volatile)
+    }
+    /// Build a call to the `llvm.memset` intrinsic that fills `len` bytes at `dest` with the
+    /// byte `val`.
+    pub fn build_memset(
+        &self,
+        dest: &Value,
+        val: &Value,
+        len: &Value,
+        align: usize,
+        volatile: bool,
+    ) -> &Value {
+        let ctx = dest.get_context();
+        assert_eq!(val.get_type(), Type::get::<u8>(ctx));
+        let dest = self.build_byte_ptr_cast(dest);
+        let types = [dest.get_type(), len.get_type()];
+        let func = Intrinsic::MemSet.declare(self.get_module(), &types);
+        let call = self.build_call(func, &[dest, val, len, volatile.compile(ctx)]);
+        if align > 0 {
+            unsafe { core::LLVMSetInstrParamAlignment(call.into(), 1, align as c_uint) }
+        }
+        call
+    }
+    fn build_mem_transfer(
+        &self,
+        intrinsic: Intrinsic,
+        dest: &Value,
+        src: &Value,
+        len: &Value,
+        align: usize,
+        volatile: bool,
+    ) -> &Value {
+        let ctx = dest.get_context();
+        let (dest, src) = (self.build_byte_ptr_cast(dest), self.build_byte_ptr_cast(src));
+        let types = [dest.get_type(), src.get_type(), len.get_type()];
+        let func = intrinsic.declare(self.get_module(), &types);
+        let call = self.build_call(func, &[dest, src, len, volatile.compile(ctx)]);
+        if align > 0 {
+            unsafe {
+                core::LLVMSetInstrParamAlignment(call.into(), 1, align as c_uint);
+                core::LLVMSetInstrParamAlignment(call.into(), 2, align as c_uint);
+            }
+        }
+        call
+    }
+    /// Cast the pointer `ptr` to an `i8` pointer in the same address space.
+    fn build_byte_ptr_cast<'a>(&'a self, ptr: &'a Value) -> &'a Value {
+        let ty = ptr.get_type();
+        assert!(ty.is_pointer(), "expected pointer, got {:?}", ty);
+        unsafe {
+            let space = core::LLVMGetPointerAddressSpace(ty.into());
+            let byte = core::LLVMInt8TypeInContext(ty.get_context().into());
+            let byte_ptr: &Type = core::LLVMPointerType(byte, space).into();
+            if ty == byte_ptr {
+                ptr
+            } else {
+                self.build_bit_cast(ptr, byte_ptr)
+            }
+        }
+    }
+    /// Returns the module that contains the block this builder is positioned in.
+    fn get_module(&self) -> &Module {
+        let func = self.get_insert_block()
+            .get_parent()
+            .expect("builder is not positioned in a function");
+        unsafe { core::LLVMGetGlobalParent(func.into()) }.into()
+    }
     /// Build an instruction that store the value `val` in the pointer `ptr`.
     pub fn build_store(&self, val: &Value, ptr: &Value) -> &Value {
         unsafe { core::LLVMBuildStore(self.into(), val.into(), ptr.into()) }.into()
     }
+    /// Build an instruction that tells the optimizer that this point in the code can never be
+    /// reached.
+    pub fn build_unreachable(&self) -> &Value {
+        unsafe { core::LLVMBuildUnreachable(self.into()) }.into()
+    }
     /// Build an instruction that branches to the block `dest`.
     pub fn build_br(&self, dest: &BasicBlock) -> &Value {
         unsafe { core::LLVMBuildBr(self.into(), dest.into()).into() }
@@ -110,8 +310,9 @@ impl Builder {
     }
     /// Build an instruction that calls the function `func` with the arguments `args`.
     ///
-    /// This will return the return value of the function.
-    pub fn build_call(&self, func: &Function, args: &[&Value]) -> &Value {
+    /// This will return the return value of the function. `func` can be a function or inline
+    /// assembly, and if it's a function the call uses the same calling convention as it.
+    pub fn build_call(&self, func: &Value, args: &[&Value]) -> &Value {
         unsafe {
             let call = core::LLVMBuildCall(
                 self.into(),
@@ -121,13 +322,18 @@ impl Builder {
                 NULL_NAME.as_ptr(),
             );
             core::LLVMSetTailCall(call, 0);
+            if let Some(func) = Function::from_super(func) {
+                core::LLVMSetInstructionCallConv(call, core::LLVMGetFunctionCallConv(func.into()));
+            }
             call.into()
         }
     }
-    /// Build an instruction that calls the function `func` with the arguments `args`.
+    /// Build an instruction that calls the function `func` with the arguments `args`, and
+    /// mark it as a tail call.
     ///
-    /// This will return the return value of the function.
-    pub fn build_tail_call(&self, func: &Function, args: &[&Value]) -> &Value {
+    /// This will return the return value of the function. `func` can be a function or inline
+    /// assembly, and if it's a function the call uses the same calling convention as it.
+    pub fn build_tail_call(&self, func: &Value, args: &[&Value]) -> &Value {
         unsafe {
             let call = core::LLVMBuildCall(
                 self.into(),
@@ -137,6 +343,9 @@ impl Builder {
                 NULL_NAME.as_ptr(),
             );
             core::LLVMSetTailCall(call, 1);
+            if let Some(func) = Function::from_super(func) {
+                core::LLVMSetInstructionCallConv(call, core::LLVMGetFunctionCallConv(func.into()));
+            }
             call.into()
         }
     }
@@ -221,7 +430,9 @@ impl Builder {
     }
     /// Build an instruction that computes the address of a subelement of an aggregate data structure.
     ///
-    /// Basically type-safe pointer arithmetic.
+    /// Basically type-safe pointer arithmetic. This emits an `inbounds` GEP, so the result is a
+    /// poison value if the address ends up outside of the object `pointer` points into; use
+    /// `build_plain_gep` if it can.
     pub fn build_gep(&self, pointer: &Value, indices: &[&Value]) -> &Value {
         unsafe {
             core::LLVMBuildInBoundsGEP(
@@ -233,6 +444,58 @@ impl Builder {
             ).into()
         }
     }
+    /// Build an instruction that computes the address of a subelement of an aggregate data
+    /// structure, like `build_gep` but without the `inbounds` flag.
+    ///
+    /// The resulting pointer may point outside of the object `pointer` points into, at the cost
+    /// of giving the optimizer less to work with.
+    pub fn build_plain_gep(&self, pointer: &Value, indices: &[&Value]) -> &Value {
+        unsafe {
+            core::LLVMBuildGEP(
+                self.into(),
+                pointer.into(),
+                indices.as_ptr() as *mut LLVMValueRef,
+                indices.len() as c_uint,
+                NULL_NAME.as_ptr(),
+            ).into()
+        }
+    }
+    /// Build an instruction that computes the address of the field at `index` in the struct
+    /// that `pointer` points to.
+    pub fn build_struct_gep(&self, pointer: &Value, index: usize) -> &Value {
+        let ty = pointer.get_type();
+        let elem = PointerType::from_super(ty)
+            .and_then(|ptr| StructType::from_super(ptr.get_element()))
+            .unwrap_or_else(|| panic!("expected a pointer to a struct, got {:?}", ty));
+        let num_fields = elem.get_elements().len();
+        assert!(index < num_fields, "field {} is out of range for {:?}", index, elem);
+        unsafe {
+            core::LLVMBuildStructGEP(
+                self.into(),
+                pointer.into(),
+                index as c_uint,
+                NULL_NAME.as_ptr(),
+            ).into()
+        }
+    }
+    /// Build a global constant C string containing `text`, then return an `i8` pointer to it.
+    pub fn build_global_string_ptr(&self, text: &str) -> &Value {
+        let block = unsafe { core::LLVMGetInsertBlock(self.into()) };
+        assert!(
+            !block.is_null() && !unsafe { core::LLVMGetBasicBlockParent(block) }.is_null(),
+            "builder is not positioned in a function"
+        );
+        util::with_cstr(text, |ptr| unsafe {
+            core::LLVMBuildGlobalStringPtr(self.into(), ptr, NULL_NAME.as_ptr()).into()
+        })
+    }
+    /// Build an instruction that computes the number of elements between the pointers `a` and
+    /// `b`, which must have the same type.
+    pub fn build_ptr_diff(&self, a: &Value, b: &Value) -> &Value {
+        assert_eq!(a.get_type(), b.get_type());
+        unsafe { core::LLVMBuildPtrDiff(self.into(), a.into(), b.into(), NULL_NAME.as_ptr()) }
+            .into()
+    }
     /// Build an instruction that runs whichever block matches the value, or `default` if none of them matched it.
     pub fn build_switch(
         &self,
@@ -281,36 +544,103 @@ impl Builder {
     bin_op!{build_or, LLVMBuildOr}
     bin_op!{build_xor, LLVMBuildXor}
     bin_op!{build_rem, LLVMBuildSRem,LLVMBuildFRem}
+    /// Build an instruction that adds two integers, returning the result and an `i1` that is
+    /// true if the addition overflowed.
+    ///
+    /// `signed` decides whether the operands are treated as signed or unsigned integers.
+    pub fn build_checked_add(&self, signed: bool, a: &Value, b: &Value) -> (&Value, &Value) {
+        let intrinsic = if signed {
+            Intrinsic::SAddWithOverflow
+        } else {
+            Intrinsic::UAddWithOverflow
+        };
+        self.build_checked(intrinsic, a, b)
+    }
+    /// Build an instruction that subtracts two integers, returning the result and an `i1` that
+    /// is true if the subtraction overflowed.
+    ///
+    /// `signed` decides whether the operands are treated as signed or unsigned integers.
+    pub fn build_checked_sub(&self, signed: bool, a: &Value, b: &Value) -> (&Value, &Value) {
+        let intrinsic = if signed {
+            Intrinsic::SSubWithOverflow
+        } else {
+            Intrinsic::USubWithOverflow
+        };
+        self.build_checked(intrinsic, a, b)
+    }
+    /// Build an instruction that multiplies two integers, returning the result and an `i1` that
+    /// is true if the multiplication overflowed.
+    ///
+    /// `signed` decides whether the operands are treated as signed or unsigned integers.
+    pub fn build_checked_mul(&self, signed: bool, a: &Value, b: &Value) -> (&Value, &Value) {
+        let intrinsic = if signed {
+            Intrinsic::SMulWithOverflow
+        } else {
+            Intrinsic::UMulWithOverflow
+        };
+        self.build_checked(intrinsic, a, b)
+    }
+    /// Build an addition like `build_checked_add` that calls `llvm.trap` if it overflows.
+    ///
+    /// This leaves the builder positioned at the end of a new block that is only reached when
+    /// the addition didn't overflow.
+    pub fn build_trapping_add(&self, signed: bool, a: &Value, b: &Value) -> &Value {
+        let (result, overflowed) = self.build_checked_add(signed, a, b);
+        self.build_trap_if(overflowed);
+        result
+    }
+    /// Build a subtraction like `build_checked_sub` that calls `llvm.trap` if it overflows.
+    ///
+    /// This leaves the builder positioned at the end of a new block that is only reached when
+    /// the subtraction didn't overflow.
+    pub fn build_trapping_sub(&self, signed: bool, a: &Value, b: &Value) -> &Value {
+        let (result, overflowed) = self.build_checked_sub(signed, a, b);
+        self.build_trap_if(overflowed);
+        result
+    }
+    /// Build a multiplication like `build_checked_mul` that calls `llvm.trap` if it overflows.
+    ///
+    /// This leaves the builder positioned at the end of a new block that is only reached when
+    /// the multiplication didn't overflow.
+    pub fn build_trapping_mul(&self, signed: bool, a: &Value, b: &Value) -> &Value {
+        let (result, overflowed) = self.build_checked_mul(signed, a, b);
+        self.build_trap_if(overflowed);
+        result
+    }
+    fn build_checked(&self, intrinsic: Intrinsic, a: &Value, b: &Value) -> (&Value, &Value) {
+        let ty = a.get_type();
+        assert!(ty.is_integer(), "expected integers, got {:?}", ty);
+        assert_eq!(ty, b.get_type());
+        let func = intrinsic.declare(self.get_module(), &[ty]);
+        let pair = self.build_call(func, &[a, b]);
+        (self.build_extract_value(pair, 0), self.build_extract_value(pair, 1))
+    }
+    /// Branch to a block that calls `llvm.trap` if `cond` is true, then continue building in
+    /// a new block that is reached otherwise.
+    fn build_trap_if(&self, cond: &Value) {
+        let func = self.get_insert_block()
+            .get_parent()
+            .expect("builder is not positioned in a function");
+        let trap_block = func.append("trap");
+        let cont_block = func.append("cont");
+        self.build_cond_br(cond, trap_block, cont_block);
+        self.position_at_end(trap_block);
+        self.build_call(Intrinsic::Trap.declare(self.get_module(), &[]), &[]);
+        self.build_unreachable();
+        self.position_at_end(cont_block);
+    }
     /// Build an instruction to compare two values with the predicate given.
     pub fn build_cmp(&self, a: &Value, b: &Value, pred: Predicate) -> &Value {
         let (at, bt) = (a.get_type(), b.get_type());
         assert_eq!(at, bt);
+        let name = NULL_NAME.as_ptr();
         if at.is_integer() {
-            let pred = match pred {
-                Predicate::Equal => LLVMIntPredicate::LLVMIntEQ,
-                Predicate::NotEqual => LLVMIntPredicate::LLVMIntNE,
-                Predicate::GreaterThan => LLVMIntPredicate::LLVMIntSGT,
-                Predicate::GreaterThanOrEqual => LLVMIntPredicate::LLVMIntSGE,
-                Predicate::LessThan => LLVMIntPredicate::LLVMIntSLT,
-                Predicate::LessThanOrEqual => LLVMIntPredicate::LLVMIntSLE,
-            };
-            unsafe {
-                core::LLVMBuildICmp(self.into(), pred, a.into(), b.into(), NULL_NAME.as_ptr())
-            }.into()
+            unsafe { core::LLVMBuildICmp(self.into(), pred.into(), a.into(), b.into(), name) }.into()
         } else if at.is_float() {
-            let pred = match pred {
-                Predicate::Equal => LLVMRealPredicate::LLVMRealOEQ,
-                Predicate::NotEqual => LLVMRealPredicate::LLVMRealONE,
-                Predicate::GreaterThan => LLVMRealPredicate::LLVMRealOGT,
-                Predicate::GreaterThanOrEqual => LLVMRealPredicate::LLVMRealOGE,
-                Predicate::LessThan => LLVMRealPredicate::LLVMRealOLT,
-                Predicate::LessThanOrEqual => LLVMRealPredicate::LLVMRealOLE,
-            };
-            unsafe {
-                core::LLVMBuildFCmp(self.into(), pred, a.into(), b.into(), NULL_NAME.as_ptr())
-            }.into()
+            unsafe { core::LLVMBuildFCmp(self.into(), pred.into(), a.into(), b.into(), name) }.into()
         } else {
             panic!("expected numbers, got {:?}", at)
         }
     }
 }
+
@@ -0,0 +1,47 @@
+use std::marker::PhantomData;
+use std::mem;
+use ffi_ext::{self, LLVMComdatRef, LLVMComdatSelectionKind};
+
+/// A COMDAT group, which lets the linker pick a single copy of a set of globals out of all
+/// the object files that define it.
+///
+/// Globals are put into a group with `GlobalValue::set_comdat`, and groups are obtained
+/// from their module with `Module::get_or_insert_comdat`.
+pub struct Comdat(PhantomData<[u8]>);
+native_ref!(&Comdat = LLVMComdatRef);
+impl Comdat {
+    /// Set how the linker picks which copy of this group to keep.
+    pub fn set_selection_kind(&self, kind: SelectionKind) {
+        unsafe { ffi_ext::LLVMSetComdatSelectionKind(self.into(), kind.into()) }
+    }
+    /// Returns how the linker picks which copy of this group to keep.
+    pub fn get_selection_kind(&self) -> SelectionKind {
+        unsafe { ffi_ext::LLVMGetComdatSelectionKind(self.into()).into() }
+    }
+}
+
+/// How the linker picks which copy of a COMDAT group to keep.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub enum SelectionKind {
+    /// The linker may pick any copy.
+    Any = 0,
+    /// Every copy must have exactly the same contents.
+    ExactMatch = 1,
+    /// The linker picks the largest copy.
+    Largest = 2,
+    /// There may only be one copy, so a duplicate is a link error.
+    NoDuplicates = 3,
+    /// Every copy must have the same size.
+    SameSize = 4,
+}
+impl From<LLVMComdatSelectionKind> for SelectionKind {
+    fn from(kind: LLVMComdatSelectionKind) -> SelectionKind {
+        unsafe { mem::transmute(kind) }
+    }
+}
+impl From<SelectionKind> for LLVMComdatSelectionKind {
+    fn from(kind: SelectionKind) -> LLVMComdatSelectionKind {
+        unsafe { mem::transmute(kind) }
+    }
+}
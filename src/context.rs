@@ -1,4 +1,5 @@
 use ffi::prelude::LLVMContextRef;
+use libc::{c_char, c_uint};
 use ffi::{core, LLVMContext};
 use std::marker::PhantomData;
 use cbox::CBox;
@@ -22,6 +23,14 @@ impl Context {
     pub fn new() -> CBox<Self> {
         CBox::new(unsafe { core::LLVMContextCreate() })
     }
+    /// Returns the ID of the metadata kind with the name given, like `range` or `tbaa`,
+    /// registering it if it's a new custom kind.
+    pub fn get_md_kind_id(&self, name: &str) -> u32 {
+        unsafe {
+            let ptr = name.as_ptr() as *const c_char;
+            core::LLVMGetMDKindIDInContext(self.into(), ptr, name.len() as c_uint)
+        }
+    }
 }
 dispose!(Context, LLVMContext, core::LLVMContextDispose);
 
@@ -0,0 +1,423 @@
+//! Debug information, mirroring the `DIBuilder` part of LLVM's C API, which takes a lot of
+//! arguments per call.
+use libc::{c_char, c_int, c_uint, size_t};
+use ffi::debuginfo::{self, LLVMDIFlags, LLVMDWARFEmissionKind, LLVMDWARFSourceLanguage};
+use ffi::prelude::{LLVMDIBuilderRef, LLVMMetadataRef};
+use ffi::LLVMOpaqueDIBuilder;
+use cbox::CSemiBox;
+use std::marker::PhantomData;
+use std::ptr;
+use block::BasicBlock;
+use compile::Compile;
+use context::GetContext;
+use ffi_ext;
+use module::{Module, ModuleFlagBehavior};
+use value::{Function, Linkage, Value};
+
+/// A piece of debug information that other debug information can be nested in, like a
+/// file, compile unit, function or lexical block.
+pub struct DIScope(PhantomData<[u8]>);
+native_ref!(&DIScope = LLVMMetadataRef);
+
+/// The debug information for a source-level type.
+pub struct DIType(PhantomData<[u8]>);
+native_ref!(&DIType = LLVMMetadataRef);
+
+/// The debug information for a source-level local variable or parameter.
+pub struct DIVariable(PhantomData<[u8]>);
+native_ref!(&DIVariable = LLVMMetadataRef);
+
+/// The source language a compile unit is written in.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SourceLanguage {
+    /// C as standardized in ANSI C89.
+    C89,
+    /// C, without a specific standard version.
+    C,
+    /// C as standardized in ISO C99.
+    C99,
+    /// C as standardized in ISO C11.
+    C11,
+    /// C++ as standardized in ISO C++98, or without a specific standard version.
+    CPlusPlus,
+    /// C++ as standardized in ISO C++11.
+    CPlusPlus11,
+    /// C++ as standardized in ISO C++14.
+    CPlusPlus14,
+    /// The D programming language.
+    D,
+    /// The Go programming language.
+    Go,
+    /// The Haskell programming language.
+    Haskell,
+    /// The Julia programming language.
+    Julia,
+    /// The OCaml programming language.
+    OCaml,
+    /// The Python programming language.
+    Python,
+    /// The Rust programming language.
+    Rust,
+    /// The Swift programming language.
+    Swift,
+}
+impl From<SourceLanguage> for LLVMDWARFSourceLanguage {
+    fn from(lang: SourceLanguage) -> LLVMDWARFSourceLanguage {
+        use self::LLVMDWARFSourceLanguage::*;
+        match lang {
+            SourceLanguage::C89 => LLVMDWARFSourceLanguageC89,
+            SourceLanguage::C => LLVMDWARFSourceLanguageC,
+            SourceLanguage::C99 => LLVMDWARFSourceLanguageC99,
+            SourceLanguage::C11 => LLVMDWARFSourceLanguageC11,
+            SourceLanguage::CPlusPlus => LLVMDWARFSourceLanguageC_plus_plus,
+            SourceLanguage::CPlusPlus11 => LLVMDWARFSourceLanguageC_plus_plus_11,
+            SourceLanguage::CPlusPlus14 => LLVMDWARFSourceLanguageC_plus_plus_14,
+            SourceLanguage::D => LLVMDWARFSourceLanguageD,
+            SourceLanguage::Go => LLVMDWARFSourceLanguageGo,
+            SourceLanguage::Haskell => LLVMDWARFSourceLanguageHaskell,
+            SourceLanguage::Julia => LLVMDWARFSourceLanguageJulia,
+            SourceLanguage::OCaml => LLVMDWARFSourceLanguageOCaml,
+            SourceLanguage::Python => LLVMDWARFSourceLanguagePython,
+            SourceLanguage::Rust => LLVMDWARFSourceLanguageRust,
+            SourceLanguage::Swift => LLVMDWARFSourceLanguageSwift,
+        }
+    }
+}
+
+/// How the bits of a basic type should be interpreted by a debugger.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum TypeEncoding {
+    /// A machine address.
+    Address = 0x01,
+    /// A boolean, where zero is false and anything else is true.
+    Boolean = 0x02,
+    /// A binary floating-point number.
+    Float = 0x04,
+    /// A signed integer.
+    Signed = 0x05,
+    /// A signed character.
+    SignedChar = 0x06,
+    /// An unsigned integer.
+    Unsigned = 0x07,
+    /// An unsigned character.
+    UnsignedChar = 0x08,
+}
+
+/// This provides an API for creating DWARF debug information and attaching it to a module.
+///
+/// The debug information is finalized when the builder is dropped, which must happen before
+/// the module is compiled.
+pub struct DebugInfoBuilder(PhantomData<[u8]>);
+native_ref!(&DebugInfoBuilder = LLVMDIBuilderRef);
+dispose!{DebugInfoBuilder, LLVMOpaqueDIBuilder, |builder| {
+    debuginfo::LLVMDIBuilderFinalize(builder);
+    debuginfo::LLVMDisposeDIBuilder(builder)
+}}
+impl DebugInfoBuilder {
+    /// Create a new debug info builder for the module given.
+    ///
+    /// This also adds the `Debug Info Version` module flag if the module doesn't have it
+    /// yet, since LLVM drops debug information from modules without it.
+    pub fn new(module: &Module) -> CSemiBox<DebugInfoBuilder> {
+        if module.get_flag("Debug Info Version").is_none() {
+            let version = unsafe { debuginfo::LLVMDebugMetadataVersion() };
+            let version = version.compile(module.get_context());
+            module.add_flag(ModuleFlagBehavior::Warning, "Debug Info Version", version);
+        }
+        CSemiBox::new(unsafe { debuginfo::LLVMCreateDIBuilder(module.into()) })
+    }
+    /// Create a file with the name and directory given.
+    pub fn create_file(&self, name: &str, directory: &str) -> &DIScope {
+        unsafe {
+            debuginfo::LLVMDIBuilderCreateFile(
+                self.into(),
+                name.as_ptr() as *const c_char,
+                name.len() as size_t,
+                directory.as_ptr() as *const c_char,
+                directory.len() as size_t,
+            ).into()
+        }
+    }
+    /// Create the compile unit for the module, which is the root of all its debug information.
+    ///
+    /// `producer` names the compiler that produced the module.
+    pub fn create_compile_unit(
+        &self,
+        lang: SourceLanguage,
+        file: &DIScope,
+        producer: &str,
+        optimized: bool,
+    ) -> &DIScope {
+        unsafe {
+            ffi_ext::LLVMDIBuilderCreateCompileUnit(
+                self.into(),
+                lang.into(),
+                file.into(),
+                producer.as_ptr() as *const c_char,
+                producer.len() as size_t,
+                optimized as c_int,
+                ptr::null(),
+                0,
+                0,
+                ptr::null(),
+                0,
+                LLVMDWARFEmissionKind::LLVMDWARFEmissionKindFull,
+                0,
+                0,
+                0,
+                ptr::null(),
+                0,
+                ptr::null(),
+                0,
+            ).into()
+        }
+    }
+    /// Create the debug information for a function defined at `line` in `file`, and attach it
+    /// to `func`.
+    ///
+    /// `ty` should be made with `create_subroutine_type`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_function(
+        &self,
+        func: &Function,
+        scope: &DIScope,
+        name: &str,
+        file: &DIScope,
+        line: usize,
+        ty: &DIType,
+        optimized: bool,
+    ) -> &DIScope {
+        let linkage_name = func.get_name().unwrap_or("");
+        let local = func.get_linkage() == Linkage::Internal
+            || func.get_linkage() == Linkage::Private;
+        unsafe {
+            let subprogram = ffi_ext::LLVMDIBuilderCreateFunction(
+                self.into(),
+                scope.into(),
+                name.as_ptr() as *const c_char,
+                name.len() as size_t,
+                linkage_name.as_ptr() as *const c_char,
+                linkage_name.len() as size_t,
+                file.into(),
+                line as c_uint,
+                ty.into(),
+                local as c_int,
+                1,
+                line as c_uint,
+                LLVMDIFlags::LLVMDIFlagPrototyped,
+                optimized as c_int,
+            );
+            ffi_ext::LLVMSetSubprogram(func.into(), subprogram);
+            subprogram.into()
+        }
+    }
+    /// Create a lexical block starting at `line` and `column` in `file`, nested in `scope`.
+    pub fn create_lexical_block(
+        &self,
+        scope: &DIScope,
+        file: &DIScope,
+        line: usize,
+        column: usize,
+    ) -> &DIScope {
+        unsafe {
+            ffi_ext::LLVMDIBuilderCreateLexicalBlock(
+                self.into(),
+                scope.into(),
+                file.into(),
+                line as c_uint,
+                column as c_uint,
+            ).into()
+        }
+    }
+    /// Create a basic type, like `int` or `double`, with the size in bits given.
+    pub fn create_basic_type(&self, name: &str, size: u64, encoding: TypeEncoding) -> &DIType {
+        unsafe {
+            ffi_ext::LLVMDIBuilderCreateBasicType(
+                self.into(),
+                name.as_ptr() as *const c_char,
+                name.len() as size_t,
+                size,
+                encoding as c_uint,
+                LLVMDIFlags::LLVMDIFlagZero,
+            ).into()
+        }
+    }
+    /// Create a pointer type to `pointee`, with the size and alignment in bits given.
+    pub fn create_pointer_type(&self, pointee: &DIType, size: u64, align: u32) -> &DIType {
+        unsafe {
+            ffi_ext::LLVMDIBuilderCreatePointerType(
+                self.into(),
+                pointee.into(),
+                size,
+                align,
+                0,
+                ptr::null(),
+                0,
+            ).into()
+        }
+    }
+    /// Create a member of a struct, with the size, alignment and offset in bits given.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_member_type(
+        &self,
+        scope: &DIScope,
+        name: &str,
+        file: &DIScope,
+        line: usize,
+        size: u64,
+        align: u32,
+        offset: u64,
+        ty: &DIType,
+    ) -> &DIType {
+        unsafe {
+            ffi_ext::LLVMDIBuilderCreateMemberType(
+                self.into(),
+                scope.into(),
+                name.as_ptr() as *const c_char,
+                name.len() as size_t,
+                file.into(),
+                line as c_uint,
+                size,
+                align,
+                offset,
+                LLVMDIFlags::LLVMDIFlagZero,
+                ty.into(),
+            ).into()
+        }
+    }
+    /// Create a struct type made of the members given, which should be made with
+    /// `create_member_type`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_struct_type(
+        &self,
+        scope: &DIScope,
+        name: &str,
+        file: &DIScope,
+        line: usize,
+        size: u64,
+        align: u32,
+        members: &[&DIType],
+    ) -> &DIType {
+        unsafe {
+            ffi_ext::LLVMDIBuilderCreateStructType(
+                self.into(),
+                scope.into(),
+                name.as_ptr() as *const c_char,
+                name.len() as size_t,
+                file.into(),
+                line as c_uint,
+                size,
+                align,
+                LLVMDIFlags::LLVMDIFlagZero,
+                ptr::null_mut(),
+                members.as_ptr() as *mut LLVMMetadataRef,
+                members.len() as c_uint,
+                0,
+                ptr::null_mut(),
+                ptr::null(),
+                0,
+            ).into()
+        }
+    }
+    /// Create the type of a function that returns `ret`, or nothing if it's `None`, and takes
+    /// the parameters given.
+    pub fn create_subroutine_type(
+        &self,
+        file: &DIScope,
+        ret: Option<&DIType>,
+        params: &[&DIType],
+    ) -> &DIType {
+        let mut types: Vec<LLVMMetadataRef> = Vec::with_capacity(params.len() + 1);
+        types.push(ret.map_or(ptr::null_mut(), |ret| ret.into()));
+        types.extend(params.iter().map(|&param| -> LLVMMetadataRef { param.into() }));
+        unsafe {
+            ffi_ext::LLVMDIBuilderCreateSubroutineType(
+                self.into(),
+                file.into(),
+                types.as_mut_ptr(),
+                types.len() as c_uint,
+                LLVMDIFlags::LLVMDIFlagZero,
+            ).into()
+        }
+    }
+    /// Create a local variable declared at `line` in `file`, nested in `scope`.
+    pub fn create_auto_variable(
+        &self,
+        scope: &DIScope,
+        name: &str,
+        file: &DIScope,
+        line: usize,
+        ty: &DIType,
+    ) -> &DIVariable {
+        unsafe {
+            ffi_ext::LLVMDIBuilderCreateAutoVariable(
+                self.into(),
+                scope.into(),
+                name.as_ptr() as *const c_char,
+                name.len() as size_t,
+                file.into(),
+                line as c_uint,
+                ty.into(),
+                1,
+                LLVMDIFlags::LLVMDIFlagZero,
+                0,
+            ).into()
+        }
+    }
+    /// Create the variable for parameter number `arg_no` of a function, counting from 1.
+    pub fn create_parameter_variable(
+        &self,
+        scope: &DIScope,
+        name: &str,
+        arg_no: usize,
+        file: &DIScope,
+        line: usize,
+        ty: &DIType,
+    ) -> &DIVariable {
+        unsafe {
+            ffi_ext::LLVMDIBuilderCreateParameterVariable(
+                self.into(),
+                scope.into(),
+                name.as_ptr() as *const c_char,
+                name.len() as size_t,
+                arg_no as c_uint,
+                file.into(),
+                line as c_uint,
+                ty.into(),
+                1,
+                LLVMDIFlags::LLVMDIFlagZero,
+            ).into()
+        }
+    }
+    /// Declare that `var` is stored at `storage`, which is usually an `alloca`, by adding a
+    /// call to `llvm.dbg.declare` to the end of `block`.
+    pub fn insert_declare_at_end(
+        &self,
+        storage: &Value,
+        var: &DIVariable,
+        line: usize,
+        column: usize,
+        scope: &DIScope,
+        block: &BasicBlock,
+    ) {
+        unsafe {
+            let context = storage.get_type().get_context();
+            let loc = debuginfo::LLVMDIBuilderCreateDebugLocation(
+                context.into(),
+                line as c_uint,
+                column as c_uint,
+                scope.into(),
+                ptr::null_mut(),
+            );
+            let expr = ffi_ext::LLVMDIBuilderCreateExpression(self.into(), ptr::null_mut(), 0);
+            ffi_ext::LLVMDIBuilderInsertDeclareAtEnd(
+                self.into(),
+                storage.into(),
+                var.into(),
+                expr,
+                loc,
+                block.into(),
+            );
+        }
+    }
+}
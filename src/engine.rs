@@ -4,11 +4,16 @@ use ffi::execution_engine as engine;
 use ffi::execution_engine::*;
 use ffi::target_machine::LLVMCodeModel;
 use cbox::{CBox, CSemiBox, DisposeRef};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::io::Result as IoResult;
 use std::marker::PhantomData;
-use std::{mem, ptr};
+use std::{mem, process, ptr, slice};
 use compile::Compile;
 use context::{Context, GetContext};
+use ffi_ext;
 use module::Module;
+use object::ObjectFile;
 use types::{StructType, Type};
 use util::{self, Sub};
 use value::{Function, Value};
@@ -91,12 +96,17 @@ where
 }
 
 /// The options to pass to the MCJIT backend.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Default)]
 pub struct JitOptions {
     /// The degree to which optimizations should be done, between 0 and 3.
     ///
     /// 0 represents no optimizations, 3 represents maximum optimization
     pub opt_level: usize,
+    /// Whether to register the compiled code with GDB's JIT interface, so debuggers can see
+    /// its symbols and debug information.
+    ///
+    /// This is also needed for `JitEngine::write_perf_map`.
+    pub gdb_registration: bool,
 }
 /// The MCJIT backend, which compiles functions and values into machine code.
 pub struct JitEngine(PhantomData<[u8]>);
@@ -151,11 +161,51 @@ impl<'a> JitEngine {
         let ptr: &u64 = self.get_global(function);
         mem::transmute(ptr)
     }
+    /// Write the address, size and name of every function compiled by a `JitEngine` that has
+    /// `gdb_registration` enabled to `/tmp/perf-<pid>.map`, so `perf` can symbolize them.
+    ///
+    /// This covers every such engine in the process, so it should be called again after
+    /// compiling more code.
+    ///
+    /// # Safety
+    ///
+    /// This reads GDB's JIT descriptor without the lock LLVM guards it with, so no other
+    /// thread may create, compile with or drop a `JitEngine` while this runs.
+    pub unsafe fn write_perf_map() -> IoResult<()> {
+        let path = format!("/tmp/perf-{}.map", process::id());
+        let mut file = BufWriter::new(try!(File::create(path)));
+        let mut entry = ffi_ext::__jit_debug_descriptor.first_entry;
+        while !entry.is_null() {
+            let bytes = slice::from_raw_parts(
+                (*entry).symfile_addr as *const u8,
+                (*entry).symfile_size as usize,
+            );
+            // The registered objects have their sections moved to where they're loaded.
+            if let Ok(object) = ObjectFile::from_bytes(bytes) {
+                let code: Vec<_> = object
+                    .sections()
+                    .filter(|section| section.name.starts_with(".text"))
+                    .map(|section| (section.address, section.contents.len() as u64))
+                    .collect();
+                for symbol in object.symbols() {
+                    let address = symbol.address as u64;
+                    let is_code = code.iter()
+                        .any(|&(start, size)| address >= start && address < start + size);
+                    if is_code && symbol.size > 0 {
+                        try!(writeln!(file, "{:x} {:x} {}", address, symbol.size, symbol.name));
+                    }
+                }
+            }
+            entry = (*entry).next_entry;
+        }
+        file.flush()
+    }
 }
 
 impl<'a> ExecutionEngine<'a> for JitEngine {
     type Options = JitOptions;
     fn new(module: &'a Module, options: JitOptions) -> Result<CSemiBox<'a, JitEngine>, CBox<str>> {
+        let gdb_registration = options.gdb_registration;
         unsafe {
             let mut ee = mem::uninitialized();
             let mut out = mem::zeroed();
@@ -168,6 +218,9 @@ impl<'a> ExecutionEngine<'a> for JitEngine {
             if target::LLVM_InitializeNativeAsmPrinter() == 1 {
                 return Err("failed to initialize native asm printer".into());
             }
+            if target::LLVM_InitializeNativeAsmParser() == 1 {
+                return Err("failed to initialize native asm parser".into());
+            }
             let mut options = LLVMMCJITCompilerOptions {
                 OptLevel: options.opt_level as c_uint,
                 CodeModel: LLVMCodeModel::LLVMCodeModelDefault,
@@ -190,6 +243,9 @@ impl<'a> ExecutionEngine<'a> for JitEngine {
                 &mut out,
             );
             if result == 0 {
+                if !gdb_registration {
+                    ffi_ext::LLVMRsUnregisterGDBListener(ee);
+                }
                 Ok(ee.into())
             } else {
                 Err(CBox::new(out))
@@ -0,0 +1,273 @@
+//! Bindings to the parts of LLVM's C API that `llvm-sys` doesn't expose yet, and to the
+//! functions in `wrappers/llvm_rs.cpp` that fill in what the C API is missing.
+#![allow(non_snake_case)]
+use libc::{c_char, c_uint, size_t};
+use ffi::debuginfo::{LLVMDIFlags, LLVMDWARFEmissionKind, LLVMDWARFSourceLanguage};
+use ffi::execution_engine::LLVMExecutionEngineRef;
+use ffi::prelude::{LLVMAttributeRef, LLVMBasicBlockRef, LLVMBool, LLVMBuilderRef, LLVMContextRef,
+                   LLVMDIBuilderRef, LLVMMetadataRef, LLVMModuleRef, LLVMTypeRef, LLVMValueRef};
+
+pub enum LLVMComdat {}
+pub type LLVMComdatRef = *mut LLVMComdat;
+pub enum LLVMOpaqueNamedMDNode {}
+pub type LLVMNamedMDNodeRef = *mut LLVMOpaqueNamedMDNode;
+
+/// An object registered with GDB's JIT interface.
+#[repr(C)]
+pub struct JITCodeEntry {
+    pub next_entry: *const JITCodeEntry,
+    pub prev_entry: *const JITCodeEntry,
+    pub symfile_addr: *const c_char,
+    pub symfile_size: u64,
+}
+
+/// The list of objects registered with GDB's JIT interface, which GDB reads.
+#[repr(C)]
+pub struct JITDescriptor {
+    pub version: u32,
+    pub action_flag: u32,
+    pub relevant_entry: *const JITCodeEntry,
+    pub first_entry: *const JITCodeEntry,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LLVMUnnamedAddr {
+    LLVMNoUnnamedAddr = 0,
+    LLVMLocalUnnamedAddr = 1,
+    LLVMGlobalUnnamedAddr = 2,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LLVMComdatSelectionKind {
+    LLVMAnyComdatSelectionKind = 0,
+    LLVMExactMatchComdatSelectionKind = 1,
+    LLVMLargestComdatSelectionKind = 2,
+    LLVMNoDuplicatesComdatSelectionKind = 3,
+    LLVMSameSizeComdatSelectionKind = 4,
+}
+
+pub const LLVMConstantAsMetadataMetadataKind: c_uint = 1;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LLVMModuleFlagBehavior {
+    LLVMModuleFlagBehaviorError = 0,
+    LLVMModuleFlagBehaviorWarning = 1,
+    LLVMModuleFlagBehaviorRequire = 2,
+    LLVMModuleFlagBehaviorOverride = 3,
+    LLVMModuleFlagBehaviorAppend = 4,
+    LLVMModuleFlagBehaviorAppendUnique = 5,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LLVMInlineAsmDialect {
+    LLVMInlineAsmDialectATT = 0,
+    LLVMInlineAsmDialectIntel = 1,
+}
+
+extern "C" {
+    pub fn LLVMCreateTypeAttribute(
+        C: LLVMContextRef,
+        KindID: c_uint,
+        type_ref: LLVMTypeRef,
+    ) -> LLVMAttributeRef;
+    pub fn LLVMGetTypeAttributeValue(A: LLVMAttributeRef) -> LLVMTypeRef;
+    pub fn LLVMIsTypeAttribute(A: LLVMAttributeRef) -> LLVMBool;
+    pub fn LLVMGetUnnamedAddress(Global: LLVMValueRef) -> LLVMUnnamedAddr;
+    pub fn LLVMSetUnnamedAddress(Global: LLVMValueRef, UnnamedAddr: LLVMUnnamedAddr);
+    pub fn LLVMGetOrInsertComdat(M: LLVMModuleRef, Name: *const c_char) -> LLVMComdatRef;
+    pub fn LLVMGetComdat(V: LLVMValueRef) -> LLVMComdatRef;
+    pub fn LLVMSetComdat(V: LLVMValueRef, C: LLVMComdatRef);
+    pub fn LLVMGetComdatSelectionKind(C: LLVMComdatRef) -> LLVMComdatSelectionKind;
+    pub fn LLVMSetComdatSelectionKind(C: LLVMComdatRef, Kind: LLVMComdatSelectionKind);
+    pub fn LLVMGetFirstGlobalAlias(M: LLVMModuleRef) -> LLVMValueRef;
+    pub fn LLVMGetNextGlobalAlias(GA: LLVMValueRef) -> LLVMValueRef;
+    pub fn LLVMGetFirstNamedMetadata(M: LLVMModuleRef) -> LLVMNamedMDNodeRef;
+    pub fn LLVMGetNextNamedMetadata(NamedMDNode: LLVMNamedMDNodeRef) -> LLVMNamedMDNodeRef;
+    pub fn LLVMGetNamedMetadataName(
+        NamedMD: LLVMNamedMDNodeRef,
+        NameLen: *mut size_t,
+    ) -> *const c_char;
+    pub fn LLVMAliasGetAliasee(Alias: LLVMValueRef) -> LLVMValueRef;
+    pub fn LLVMAliasSetAliasee(Alias: LLVMValueRef, Aliasee: LLVMValueRef);
+    pub fn LLVMAddModuleFlag(
+        M: LLVMModuleRef,
+        Behavior: LLVMModuleFlagBehavior,
+        Key: *const c_char,
+        KeyLen: size_t,
+        Val: LLVMMetadataRef,
+    );
+    pub fn LLVMGetModuleFlag(M: LLVMModuleRef, Key: *const c_char, KeyLen: size_t)
+        -> LLVMMetadataRef;
+    pub fn LLVMSetModuleInlineAsm2(M: LLVMModuleRef, Asm: *const c_char, Len: size_t);
+    pub fn LLVMAppendModuleInlineAsm(M: LLVMModuleRef, Asm: *const c_char, Len: size_t);
+    pub fn LLVMGetModuleInlineAsm(M: LLVMModuleRef, Len: *mut size_t) -> *const c_char;
+    // This is declared with the `CanThrow` argument added in LLVM 13.
+    pub fn LLVMGetInlineAsm(
+        Ty: LLVMTypeRef,
+        AsmString: *const c_char,
+        AsmStringSize: size_t,
+        Constraints: *const c_char,
+        ConstraintsSize: size_t,
+        HasSideEffects: LLVMBool,
+        IsAlignStack: LLVMBool,
+        Dialect: LLVMInlineAsmDialect,
+        CanThrow: LLVMBool,
+    ) -> LLVMValueRef;
+    pub fn LLVMGetMetadataKind(Metadata: LLVMMetadataRef) -> c_uint;
+    pub fn LLVMIsLiteralStruct(StructTy: LLVMTypeRef) -> LLVMBool;
+    // `llvm-sys` declares this without the `SysRoot` and `SDK` arguments added in LLVM 11.
+    pub fn LLVMDIBuilderCreateCompileUnit(
+        Builder: LLVMDIBuilderRef,
+        Lang: LLVMDWARFSourceLanguage,
+        FileRef: LLVMMetadataRef,
+        Producer: *const c_char,
+        ProducerLen: size_t,
+        isOptimized: LLVMBool,
+        Flags: *const c_char,
+        FlagsLen: size_t,
+        RuntimeVer: c_uint,
+        SplitName: *const c_char,
+        SplitNameLen: size_t,
+        Kind: LLVMDWARFEmissionKind,
+        DWOId: c_uint,
+        SplitDebugInlining: LLVMBool,
+        DebugInfoForProfiling: LLVMBool,
+        SysRoot: *const c_char,
+        SysRootLen: size_t,
+        SDK: *const c_char,
+        SDKLen: size_t,
+    ) -> LLVMMetadataRef;
+    pub fn LLVMDIBuilderCreateFunction(
+        Builder: LLVMDIBuilderRef,
+        Scope: LLVMMetadataRef,
+        Name: *const c_char,
+        NameLen: size_t,
+        LinkageName: *const c_char,
+        LinkageNameLen: size_t,
+        File: LLVMMetadataRef,
+        LineNo: c_uint,
+        Ty: LLVMMetadataRef,
+        IsLocalToUnit: LLVMBool,
+        IsDefinition: LLVMBool,
+        ScopeLine: c_uint,
+        Flags: LLVMDIFlags,
+        IsOptimized: LLVMBool,
+    ) -> LLVMMetadataRef;
+    pub fn LLVMDIBuilderCreateLexicalBlock(
+        Builder: LLVMDIBuilderRef,
+        Scope: LLVMMetadataRef,
+        File: LLVMMetadataRef,
+        Line: c_uint,
+        Column: c_uint,
+    ) -> LLVMMetadataRef;
+    pub fn LLVMDIBuilderCreateSubroutineType(
+        Builder: LLVMDIBuilderRef,
+        File: LLVMMetadataRef,
+        ParameterTypes: *mut LLVMMetadataRef,
+        NumParameterTypes: c_uint,
+        Flags: LLVMDIFlags,
+    ) -> LLVMMetadataRef;
+    pub fn LLVMDIBuilderCreateBasicType(
+        Builder: LLVMDIBuilderRef,
+        Name: *const c_char,
+        NameLen: size_t,
+        SizeInBits: u64,
+        Encoding: c_uint,
+        Flags: LLVMDIFlags,
+    ) -> LLVMMetadataRef;
+    pub fn LLVMDIBuilderCreatePointerType(
+        Builder: LLVMDIBuilderRef,
+        PointeeTy: LLVMMetadataRef,
+        SizeInBits: u64,
+        AlignInBits: u32,
+        AddressSpace: c_uint,
+        Name: *const c_char,
+        NameLen: size_t,
+    ) -> LLVMMetadataRef;
+    pub fn LLVMDIBuilderCreateStructType(
+        Builder: LLVMDIBuilderRef,
+        Scope: LLVMMetadataRef,
+        Name: *const c_char,
+        NameLen: size_t,
+        File: LLVMMetadataRef,
+        LineNumber: c_uint,
+        SizeInBits: u64,
+        AlignInBits: u32,
+        Flags: LLVMDIFlags,
+        DerivedFrom: LLVMMetadataRef,
+        Elements: *mut LLVMMetadataRef,
+        NumElements: c_uint,
+        RunTimeLang: c_uint,
+        VTableHolder: LLVMMetadataRef,
+        UniqueId: *const c_char,
+        UniqueIdLen: size_t,
+    ) -> LLVMMetadataRef;
+    pub fn LLVMDIBuilderCreateMemberType(
+        Builder: LLVMDIBuilderRef,
+        Scope: LLVMMetadataRef,
+        Name: *const c_char,
+        NameLen: size_t,
+        File: LLVMMetadataRef,
+        LineNo: c_uint,
+        SizeInBits: u64,
+        AlignInBits: u32,
+        OffsetInBits: u64,
+        Flags: LLVMDIFlags,
+        Ty: LLVMMetadataRef,
+    ) -> LLVMMetadataRef;
+    pub fn LLVMDIBuilderCreateAutoVariable(
+        Builder: LLVMDIBuilderRef,
+        Scope: LLVMMetadataRef,
+        Name: *const c_char,
+        NameLen: size_t,
+        File: LLVMMetadataRef,
+        LineNo: c_uint,
+        Ty: LLVMMetadataRef,
+        AlwaysPreserve: LLVMBool,
+        Flags: LLVMDIFlags,
+        AlignInBits: u32,
+    ) -> LLVMMetadataRef;
+    pub fn LLVMDIBuilderCreateParameterVariable(
+        Builder: LLVMDIBuilderRef,
+        Scope: LLVMMetadataRef,
+        Name: *const c_char,
+        NameLen: size_t,
+        ArgNo: c_uint,
+        File: LLVMMetadataRef,
+        LineNo: c_uint,
+        Ty: LLVMMetadataRef,
+        AlwaysPreserve: LLVMBool,
+        Flags: LLVMDIFlags,
+    ) -> LLVMMetadataRef;
+    pub fn LLVMDIBuilderCreateExpression(
+        Builder: LLVMDIBuilderRef,
+        Addr: *mut u64,
+        Length: size_t,
+    ) -> LLVMMetadataRef;
+    pub fn LLVMDIBuilderInsertDeclareAtEnd(
+        Builder: LLVMDIBuilderRef,
+        Storage: LLVMValueRef,
+        VarInfo: LLVMMetadataRef,
+        Expr: LLVMMetadataRef,
+        DebugLoc: LLVMMetadataRef,
+        Block: LLVMBasicBlockRef,
+    ) -> LLVMValueRef;
+    pub fn LLVMGetSubprogram(Func: LLVMValueRef) -> LLVMMetadataRef;
+    pub fn LLVMSetSubprogram(Func: LLVMValueRef, SP: LLVMMetadataRef);
+    pub fn LLVMSetCurrentDebugLocation2(Builder: LLVMBuilderRef, Loc: LLVMMetadataRef);
+    pub fn LLVMNormalizeTargetTriple(Triple: *const c_char) -> *mut c_char;
+    #[allow(non_upper_case_globals)]
+    pub static __jit_debug_descriptor: JITDescriptor;
+}
+
+extern "C" {
+    pub fn LLVMRsUnregisterGDBListener(EE: LLVMExecutionEngineRef);
+    pub fn LLVMRsIsMaterializable(GV: LLVMValueRef) -> LLVMBool;
+    pub fn LLVMRsMaterialize(GV: LLVMValueRef, OutMessage: *mut *mut c_char) -> LLVMBool;
+    pub fn LLVMRsMaterializeAll(M: LLVMModuleRef, OutMessage: *mut *mut c_char) -> LLVMBool;
+    pub fn LLVMRsRemoveGlobalFromParent(GV: LLVMValueRef);
+}
@@ -0,0 +1,315 @@
+//! Declarations for the intrinsic functions that LLVM provides.
+//!
+//! Most intrinsics are overloaded on the types of their operands, and each overload
+//! has a mangled name (like `llvm.sqrt.f64`) that must match its signature exactly.
+//! `Intrinsic::declare` takes care of both, so you only need to give the types.
+use ffi::{core, LLVMTypeKind};
+use libc::{c_char, c_uint};
+use context::{Context, GetContext};
+use module::Module;
+use types::{FunctionType, PointerType, StructType, Type};
+use util;
+use value::Function;
+
+/// An intrinsic function that can be declared in a module.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Intrinsic {
+    /// `llvm.sqrt`, which takes a floating-point type.
+    Sqrt,
+    /// `llvm.sin`, which takes a floating-point type.
+    Sin,
+    /// `llvm.cos`, which takes a floating-point type.
+    Cos,
+    /// `llvm.pow`, which takes a floating-point type.
+    Pow,
+    /// `llvm.exp`, which takes a floating-point type.
+    Exp,
+    /// `llvm.exp2`, which takes a floating-point type.
+    Exp2,
+    /// `llvm.log`, which takes a floating-point type.
+    Log,
+    /// `llvm.log10`, which takes a floating-point type.
+    Log10,
+    /// `llvm.log2`, which takes a floating-point type.
+    Log2,
+    /// `llvm.fma`, which takes a floating-point type.
+    Fma,
+    /// `llvm.fabs`, which takes a floating-point type.
+    Fabs,
+    /// `llvm.minnum`, which takes a floating-point type.
+    MinNum,
+    /// `llvm.maxnum`, which takes a floating-point type.
+    MaxNum,
+    /// `llvm.copysign`, which takes a floating-point type.
+    CopySign,
+    /// `llvm.floor`, which takes a floating-point type.
+    Floor,
+    /// `llvm.ceil`, which takes a floating-point type.
+    Ceil,
+    /// `llvm.trunc`, which takes a floating-point type.
+    Trunc,
+    /// `llvm.rint`, which takes a floating-point type.
+    Rint,
+    /// `llvm.nearbyint`, which takes a floating-point type.
+    NearbyInt,
+    /// `llvm.round`, which takes a floating-point type.
+    Round,
+    /// `llvm.sadd.with.overflow`, which takes an integer type.
+    SAddWithOverflow,
+    /// `llvm.uadd.with.overflow`, which takes an integer type.
+    UAddWithOverflow,
+    /// `llvm.ssub.with.overflow`, which takes an integer type.
+    SSubWithOverflow,
+    /// `llvm.usub.with.overflow`, which takes an integer type.
+    USubWithOverflow,
+    /// `llvm.smul.with.overflow`, which takes an integer type.
+    SMulWithOverflow,
+    /// `llvm.umul.with.overflow`, which takes an integer type.
+    UMulWithOverflow,
+    /// `llvm.ctpop`, which takes an integer type.
+    Ctpop,
+    /// `llvm.ctlz`, which takes an integer type.
+    Ctlz,
+    /// `llvm.cttz`, which takes an integer type.
+    Cttz,
+    /// `llvm.bswap`, which takes an integer type.
+    Bswap,
+    /// `llvm.fshl`, which takes an integer type.
+    Fshl,
+    /// `llvm.fshr`, which takes an integer type.
+    Fshr,
+    /// `llvm.lifetime.start`, which takes a pointer type.
+    LifetimeStart,
+    /// `llvm.lifetime.end`, which takes a pointer type.
+    LifetimeEnd,
+    /// `llvm.assume`, which isn't overloaded.
+    Assume,
+    /// `llvm.expect`, which takes an integer type.
+    Expect,
+    /// `llvm.trap`, which isn't overloaded.
+    Trap,
+    /// `llvm.debugtrap`, which isn't overloaded.
+    DebugTrap,
+    /// `llvm.stacksave`, which isn't overloaded.
+    StackSave,
+    /// `llvm.stackrestore`, which isn't overloaded.
+    StackRestore,
+    /// `llvm.prefetch`, which takes a pointer type.
+    Prefetch,
+    /// `llvm.memcpy`, which takes the destination pointer, source pointer and length types.
+    MemCpy,
+    /// `llvm.memmove`, which takes the destination pointer, source pointer and length types.
+    MemMove,
+    /// `llvm.memset`, which takes the destination pointer and length types.
+    MemSet,
+}
+impl Intrinsic {
+    /// Returns the name of this intrinsic without any overload suffixes.
+    pub fn get_name(self) -> &'static str {
+        match self {
+            Intrinsic::Sqrt => "llvm.sqrt",
+            Intrinsic::Sin => "llvm.sin",
+            Intrinsic::Cos => "llvm.cos",
+            Intrinsic::Pow => "llvm.pow",
+            Intrinsic::Exp => "llvm.exp",
+            Intrinsic::Exp2 => "llvm.exp2",
+            Intrinsic::Log => "llvm.log",
+            Intrinsic::Log10 => "llvm.log10",
+            Intrinsic::Log2 => "llvm.log2",
+            Intrinsic::Fma => "llvm.fma",
+            Intrinsic::Fabs => "llvm.fabs",
+            Intrinsic::MinNum => "llvm.minnum",
+            Intrinsic::MaxNum => "llvm.maxnum",
+            Intrinsic::CopySign => "llvm.copysign",
+            Intrinsic::Floor => "llvm.floor",
+            Intrinsic::Ceil => "llvm.ceil",
+            Intrinsic::Trunc => "llvm.trunc",
+            Intrinsic::Rint => "llvm.rint",
+            Intrinsic::NearbyInt => "llvm.nearbyint",
+            Intrinsic::Round => "llvm.round",
+            Intrinsic::SAddWithOverflow => "llvm.sadd.with.overflow",
+            Intrinsic::UAddWithOverflow => "llvm.uadd.with.overflow",
+            Intrinsic::SSubWithOverflow => "llvm.ssub.with.overflow",
+            Intrinsic::USubWithOverflow => "llvm.usub.with.overflow",
+            Intrinsic::SMulWithOverflow => "llvm.smul.with.overflow",
+            Intrinsic::UMulWithOverflow => "llvm.umul.with.overflow",
+            Intrinsic::Ctpop => "llvm.ctpop",
+            Intrinsic::Ctlz => "llvm.ctlz",
+            Intrinsic::Cttz => "llvm.cttz",
+            Intrinsic::Bswap => "llvm.bswap",
+            Intrinsic::Fshl => "llvm.fshl",
+            Intrinsic::Fshr => "llvm.fshr",
+            Intrinsic::LifetimeStart => "llvm.lifetime.start",
+            Intrinsic::LifetimeEnd => "llvm.lifetime.end",
+            Intrinsic::Assume => "llvm.assume",
+            Intrinsic::Expect => "llvm.expect",
+            Intrinsic::Trap => "llvm.trap",
+            Intrinsic::DebugTrap => "llvm.debugtrap",
+            Intrinsic::StackSave => "llvm.stacksave",
+            Intrinsic::StackRestore => "llvm.stackrestore",
+            Intrinsic::Prefetch => "llvm.prefetch",
+            Intrinsic::MemCpy => "llvm.memcpy",
+            Intrinsic::MemMove => "llvm.memmove",
+            Intrinsic::MemSet => "llvm.memset",
+        }
+    }
+    /// Returns the number of types this intrinsic is overloaded on.
+    pub fn num_overloads(self) -> usize {
+        match self {
+            Intrinsic::Assume
+            | Intrinsic::Trap
+            | Intrinsic::DebugTrap
+            | Intrinsic::StackSave
+            | Intrinsic::StackRestore => 0,
+            Intrinsic::MemCpy | Intrinsic::MemMove => 3,
+            Intrinsic::MemSet => 2,
+            _ => 1,
+        }
+    }
+    /// Returns the mangled name of the overload of this intrinsic for the types given.
+    pub fn get_mangled_name(self, types: &[&Type]) -> String {
+        let mut name = self.get_name().to_owned();
+        for ty in types {
+            name.push('.');
+            name.push_str(&mangle_type(ty));
+        }
+        name
+    }
+    /// Declare the overload of this intrinsic for the types given in `module`, or return
+    /// the existing declaration if it has already been declared.
+    ///
+    /// This will panic if the number of types given doesn't match `num_overloads`.
+    pub fn declare<'a>(self, module: &'a Module, types: &[&'a Type]) -> &'a Function {
+        assert_eq!(
+            types.len(),
+            self.num_overloads(),
+            "wrong number of overload types for {}",
+            self.get_name()
+        );
+        let name = self.get_mangled_name(types);
+        match module.get_function(&name) {
+            Some(func) => func,
+            None => module.add_function(&name, self.get_signature(module.get_context(), types)),
+        }
+    }
+    /// Returns the signature of the overload of this intrinsic for the types given.
+    fn get_signature<'a>(self, ctx: &'a Context, types: &[&'a Type]) -> &'a FunctionType {
+        let void = Type::get::<()>(ctx);
+        let bool_t = Type::get::<bool>(ctx);
+        let i32_t = Type::get::<i32>(ctx);
+        let i8_ptr = PointerType::new(Type::get::<i8>(ctx));
+        match self {
+            Intrinsic::Sqrt
+            | Intrinsic::Sin
+            | Intrinsic::Cos
+            | Intrinsic::Exp
+            | Intrinsic::Exp2
+            | Intrinsic::Log
+            | Intrinsic::Log10
+            | Intrinsic::Log2
+            | Intrinsic::Fabs
+            | Intrinsic::Floor
+            | Intrinsic::Ceil
+            | Intrinsic::Trunc
+            | Intrinsic::Rint
+            | Intrinsic::NearbyInt
+            | Intrinsic::Round
+            | Intrinsic::Ctpop
+            | Intrinsic::Bswap => FunctionType::new(types[0], &[types[0]]),
+            Intrinsic::Pow
+            | Intrinsic::MinNum
+            | Intrinsic::MaxNum
+            | Intrinsic::CopySign
+            | Intrinsic::Expect => FunctionType::new(types[0], &[types[0], types[0]]),
+            Intrinsic::Fma | Intrinsic::Fshl | Intrinsic::Fshr => {
+                FunctionType::new(types[0], &[types[0], types[0], types[0]])
+            }
+            Intrinsic::SAddWithOverflow
+            | Intrinsic::UAddWithOverflow
+            | Intrinsic::SSubWithOverflow
+            | Intrinsic::USubWithOverflow
+            | Intrinsic::SMulWithOverflow
+            | Intrinsic::UMulWithOverflow => {
+                let ret = StructType::new(ctx, &[types[0], bool_t], false);
+                FunctionType::new(ret, &[types[0], types[0]])
+            }
+            Intrinsic::Ctlz | Intrinsic::Cttz => FunctionType::new(types[0], &[types[0], bool_t]),
+            Intrinsic::LifetimeStart | Intrinsic::LifetimeEnd => {
+                FunctionType::new(void, &[Type::get::<i64>(ctx), types[0]])
+            }
+            Intrinsic::Assume => FunctionType::new(void, &[bool_t]),
+            Intrinsic::Trap | Intrinsic::DebugTrap => FunctionType::new(void, &[]),
+            Intrinsic::StackSave => FunctionType::new(i8_ptr, &[]),
+            Intrinsic::StackRestore => FunctionType::new(void, &[i8_ptr]),
+            Intrinsic::Prefetch => FunctionType::new(void, &[types[0], i32_t, i32_t, i32_t]),
+            Intrinsic::MemCpy | Intrinsic::MemMove => {
+                FunctionType::new(void, &[types[0], types[1], types[2], bool_t])
+            }
+            Intrinsic::MemSet => {
+                FunctionType::new(void, &[types[0], Type::get::<u8>(ctx), types[1], bool_t])
+            }
+        }
+    }
+}
+
+/// Returns the string LLVM uses for `ty` in the mangled names of overloaded intrinsics.
+fn mangle_type(ty: &Type) -> String {
+    unsafe {
+        let raw = ty.into();
+        match core::LLVMGetTypeKind(raw) {
+            LLVMTypeKind::LLVMVoidTypeKind => "isVoid".to_owned(),
+            LLVMTypeKind::LLVMHalfTypeKind => "f16".to_owned(),
+            LLVMTypeKind::LLVMFloatTypeKind => "f32".to_owned(),
+            LLVMTypeKind::LLVMDoubleTypeKind => "f64".to_owned(),
+            LLVMTypeKind::LLVMX86_FP80TypeKind => "f80".to_owned(),
+            LLVMTypeKind::LLVMFP128TypeKind => "f128".to_owned(),
+            LLVMTypeKind::LLVMPPC_FP128TypeKind => "ppcf128".to_owned(),
+            LLVMTypeKind::LLVMX86_MMXTypeKind => "x86mmx".to_owned(),
+            LLVMTypeKind::LLVMMetadataTypeKind => "Metadata".to_owned(),
+            LLVMTypeKind::LLVMIntegerTypeKind => format!("i{}", core::LLVMGetIntTypeWidth(raw)),
+            LLVMTypeKind::LLVMPointerTypeKind => format!(
+                "p{}{}",
+                core::LLVMGetPointerAddressSpace(raw),
+                mangle_type(core::LLVMGetElementType(raw).into())
+            ),
+            LLVMTypeKind::LLVMArrayTypeKind => format!(
+                "a{}{}",
+                core::LLVMGetArrayLength(raw),
+                mangle_type(core::LLVMGetElementType(raw).into())
+            ),
+            LLVMTypeKind::LLVMVectorTypeKind => format!(
+                "v{}{}",
+                core::LLVMGetVectorSize(raw),
+                mangle_type(core::LLVMGetElementType(raw).into())
+            ),
+            LLVMTypeKind::LLVMStructTypeKind => {
+                let name = core::LLVMGetStructName(raw);
+                if name.is_null() {
+                    let count = core::LLVMCountStructElementTypes(raw);
+                    let mut mangled = "sl_".to_owned();
+                    for i in 0..count {
+                        let elem = core::LLVMStructGetTypeAtIndex(raw, i as c_uint);
+                        mangled.push_str(&mangle_type(elem.into()));
+                    }
+                    mangled.push('s');
+                    mangled
+                } else {
+                    format!("s_{}", util::to_str(name as *mut c_char))
+                }
+            }
+            LLVMTypeKind::LLVMFunctionTypeKind => {
+                let sig: &FunctionType = raw.into();
+                let mut mangled = format!("f_{}", mangle_type(sig.get_return()));
+                for param in sig.get_params() {
+                    mangled.push_str(&mangle_type(param));
+                }
+                if core::LLVMIsFunctionVarArg(raw) != 0 {
+                    mangled.push_str("vararg");
+                }
+                mangled.push('f');
+                mangled
+            }
+            _ => panic!("cannot mangle type {:?}", ty),
+        }
+    }
+}
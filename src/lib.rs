@@ -17,12 +17,18 @@ extern "C" {}
 
 #[macro_use]
 mod macros;
+mod attribute;
 mod buffer;
 mod block;
 mod builder;
+mod comdat;
 mod compile;
 mod context;
+mod debuginfo;
 mod engine;
+mod ffi_ext;
+pub mod intrinsics;
+mod metadata;
 mod module;
 mod object;
 mod target;
@@ -34,17 +40,26 @@ pub mod lto;
 pub mod link_time_optimizer;
 
 pub use cbox::{CBox, CSemiBox};
+pub use attribute::{Attribute, AttributeIndex};
 pub use builder::Builder;
 pub use block::BasicBlock;
+pub use buffer::MemoryBuffer;
+pub use comdat::{Comdat, SelectionKind};
 pub use compile::Compile;
 pub use context::{Context, GetContext};
+pub use debuginfo::{DIScope, DIType, DIVariable, DebugInfoBuilder, SourceLanguage, TypeEncoding};
 pub use engine::{ExecutionEngine, GenericValue, GenericValueCast, Interpreter, JitEngine,
                  JitOptions};
-pub use module::{AddressSpace, Functions, Module};
-pub use object::{ObjectFile, Symbol, Symbols};
-pub use target::{Target, TargetData, TargetMachine};
+pub use intrinsics::Intrinsic;
+pub use metadata::{MDNode, MDString, NamedMetadata};
+pub use module::{AddressSpace, Aliases, Functions, Globals, IrError, Module, ModuleFlagBehavior,
+                 NamedMetadatas, StructTypes};
+pub use object::{ObjectFile, Section, Sections, Symbol, Symbols};
+pub use target::{FieldLayout, FileType, StructLayout, Target, TargetData, TargetMachine, Triple};
 pub use types::*;
-pub use value::{Alias, Arg, Function, GlobalValue, GlobalVariable, Linkage, Predicate, Value};
+pub use value::{Alias, Arg, AsmDialect, CallConv, CallSite, ConstantArray, DLLStorageClass,
+                Function, GlobalValue, GlobalVariable, InlineAsm, Instruction, Linkage, Predicate,
+                ThreadLocalMode, UnnamedAddr, Value, Visibility};
 pub use util::Sub;
 pub use pass_manager::{PassManager, PassManagerBuilder, PassRegistry};
 
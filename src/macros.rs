@@ -156,12 +156,19 @@ macro_rules! native_ref(
 );
 macro_rules! to_str(
     ($ty:ty, $func:ident) => (
-        impl fmt::Debug for $ty {
+        impl fmt::Display for $ty {
             fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-                fmt.write_str(unsafe {
+                unsafe {
                     let c_str = core::$func(self.into());
-                    util::to_str(c_str)
-                })
+                    let result = fmt.write_str(util::to_str(c_str));
+                    core::LLVMDisposeMessage(c_str);
+                    result
+                }
+            }
+        }
+        impl fmt::Debug for $ty {
+            fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt::Display::fmt(self, fmt)
             }
         }
     );
@@ -0,0 +1,89 @@
+use libc::{c_char, c_uint};
+use ffi::prelude::LLVMValueRef;
+use ffi::core;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::str::{self, Utf8Error};
+use std::{fmt, mem, ptr, slice};
+use context::Context;
+use ffi_ext::{self, LLVMNamedMDNodeRef};
+use util::{self, Sub};
+use value::Value;
+
+/// A metadata string, like `!"clang"`.
+pub struct MDString(PhantomData<[u8]>);
+native_ref!(&MDString = LLVMValueRef);
+to_str!{MDString, LLVMPrintValueToString}
+deref!{MDString, Value}
+unsafe impl Sub<Value> for MDString {
+    fn is(value: &Value) -> bool {
+        unsafe { !core::LLVMIsAMDString(value.into()).is_null() }
+    }
+}
+impl MDString {
+    /// Create a new metadata string with the text given.
+    pub fn new<'a>(context: &'a Context, text: &str) -> &'a MDString {
+        unsafe {
+            let ptr = text.as_ptr() as *const c_char;
+            core::LLVMMDStringInContext(context.into(), ptr, text.len() as c_uint).into()
+        }
+    }
+    /// Returns the text of this string, or an error if it isn't valid UTF-8.
+    pub fn get_string(&self) -> Result<&str, Utf8Error> {
+        unsafe {
+            let mut len = 0;
+            let ptr = core::LLVMGetMDString(self.into(), &mut len);
+            str::from_utf8(slice::from_raw_parts(ptr as *const u8, len as usize))
+        }
+    }
+}
+
+/// A metadata node, which is a tuple of values, strings and other nodes, like `!{i32 0}`.
+///
+/// Nodes can be attached to instructions with `Instruction::set_metadata` and to modules
+/// with `Module::add_named_metadata_operand`.
+pub struct MDNode(PhantomData<[u8]>);
+native_ref!(&MDNode = LLVMValueRef);
+to_str!{MDNode, LLVMPrintValueToString}
+deref!{MDNode, Value}
+unsafe impl Sub<Value> for MDNode {
+    fn is(value: &Value) -> bool {
+        unsafe { !core::LLVMIsAMDNode(value.into()).is_null() }
+    }
+}
+impl MDNode {
+    /// Create a new metadata node from the values given, which can be constants, strings
+    /// and other nodes.
+    pub fn new<'a>(context: &'a Context, vals: &[&'a Value]) -> &'a MDNode {
+        unsafe {
+            core::LLVMMDNodeInContext(
+                context.into(),
+                vals.as_ptr() as *mut LLVMValueRef,
+                vals.len() as c_uint,
+            ).into()
+        }
+    }
+    /// Returns the operands of this node, with `None` for null operands.
+    pub fn get_operands(&self) -> Vec<Option<&Value>> {
+        unsafe {
+            let count = core::LLVMGetMDNodeNumOperands(self.into()) as usize;
+            let mut operands = vec![ptr::null_mut(); count];
+            core::LLVMGetMDNodeOperands(self.into(), operands.as_mut_ptr());
+            operands.into_iter().map(|op| util::ptr_to_null(op)).collect()
+        }
+    }
+}
+
+/// A named list of metadata nodes attached to a module, like `llvm.ident`.
+pub struct NamedMetadata(PhantomData<[u8]>);
+native_ref!(&NamedMetadata = LLVMNamedMDNodeRef);
+impl NamedMetadata {
+    /// Returns the name of this metadata, or an error if it isn't valid UTF-8.
+    pub fn get_name(&self) -> Result<&str, Utf8Error> {
+        unsafe {
+            let mut len = 0;
+            let ptr = ffi_ext::LLVMGetNamedMetadataName(self.into(), &mut len);
+            str::from_utf8(slice::from_raw_parts(ptr as *const u8, len))
+        }
+    }
+}
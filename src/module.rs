@@ -1,26 +1,34 @@
-use libc::{c_char, c_uint};
-use ffi::prelude::{LLVMModuleRef, LLVMValueRef};
+use libc::{c_char, c_uint, size_t};
+use ffi::prelude::{LLVMModuleRef, LLVMTypeRef, LLVMValueRef};
 use ffi::analysis::LLVMVerifierFailureAction;
-use ffi::{analysis, core, linker, LLVMModule};
+use ffi::{analysis, core, linker, LLVMModule, LLVMTypeKind};
 use ffi::transforms::pass_manager_builder as builder;
 use ffi::transforms::ipo;
 use ffi::bit_writer as writer;
 use ffi::bit_reader as reader;
 use ffi::ir_reader;
+use ffi::target;
 use cbox::{CBox, CSemiBox};
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::iter::{IntoIterator, Iterator};
-use std::io::{Error, ErrorKind};
+use std::io::{Error, ErrorKind, Write};
 use std::io::Result as IoResult;
-use std::{env, fmt, mem};
+use std::collections::HashSet;
+use std::{env, fmt, mem, ptr, slice, str, vec};
 use std::marker::PhantomData;
 use std::path::Path;
+use std::str::Utf8Error;
 use std::process::{Child, Command};
 use buffer::MemoryBuffer;
+use comdat::Comdat;
+use compile::Compile;
 use context::{Context, GetContext};
-use value::{Alias, Function, GlobalValue, GlobalVariable, Value};
-use types::Type;
-use util;
+use ffi_ext::{self, LLVMModuleFlagBehavior, LLVMNamedMDNodeRef};
+use metadata::{MDNode, MDString, NamedMetadata};
+use target::{TargetData, TargetMachine, Triple};
+use value::{Alias, Function, GlobalValue, GlobalVariable, Linkage, Value};
+use types::{ArrayType, FunctionType, PointerType, StructType, Type};
+use util::{self, Sub};
 
 /// Represents a single compilation unit of code.
 ///
@@ -78,6 +86,12 @@ impl Module {
             core::LLVMAddAlias(self.into(), val.get_type().into(), val.into(), ptr).into()
         })
     }
+    /// Get the COMDAT group with the name given, creating it if it doesn't exist yet.
+    pub fn get_or_insert_comdat<'a>(&'a self, name: &str) -> &'a Comdat {
+        util::with_cstr(name, |ptr| unsafe {
+            ffi_ext::LLVMGetOrInsertComdat(self.into(), ptr).into()
+        })
+    }
     /// Get the global with the name given, or `None` if no global with that name exists.
     pub fn get_global<'a>(&'a self, name: &str) -> Option<&'a GlobalValue> {
         util::with_cstr(name, |ptr| unsafe {
@@ -85,18 +99,325 @@ impl Module {
             util::ptr_to_null(ptr)
         })
     }
+    /// Delete the function or global variable given from this module, or return an error
+    /// string if it is still used, is an alias or belongs to another module.
+    pub fn remove_global(&self, global: &GlobalValue) -> Result<(), CBox<str>> {
+        if unsafe { core::LLVMGetGlobalParent(global.into()) } != self.into() {
+            Err(CBox::from("global belongs to another module"))
+        } else if let Some(func) = Function::from_super(global) {
+            func.delete()
+        } else if let Some(var) = GlobalVariable::from_super(global) {
+            var.delete()
+        } else {
+            Err(CBox::from("global is not a function or global variable"))
+        }
+    }
+    /// Make the function given run when the module is loaded, before functions with a higher
+    /// priority, by adding it to `llvm.global_ctors`.
+    ///
+    /// The function must take no arguments and return nothing.
+    pub fn add_global_ctor(&self, func: &Function, priority: u32) {
+        self.add_structor("llvm.global_ctors", func, priority)
+    }
+    /// Make the function given run when the module is unloaded, before functions with a
+    /// higher priority, by adding it to `llvm.global_dtors`.
+    ///
+    /// The function must take no arguments and return nothing.
+    pub fn add_global_dtor(&self, func: &Function, priority: u32) {
+        self.add_structor("llvm.global_dtors", func, priority)
+    }
+    /// Add a `{ i32, void ()*, i8* }` entry for the function given to the appending global
+    /// array with the name given, replacing the array if it already exists.
+    fn add_structor(&self, name: &str, func: &Function, priority: u32) {
+        let sig = func.get_signature();
+        assert!(
+            sig.get_return().is_void() && sig.num_params() == 0,
+            "{:?} must take no arguments and return nothing",
+            func.get_name()
+        );
+        let ctx = self.get_context();
+        let func_ptr_ty = PointerType::new(FunctionType::new(Type::get::<()>(ctx), &[]));
+        let data_ptr_ty = PointerType::new(Type::get::<i8>(ctx));
+        let entry_fields = [Type::get::<i32>(ctx), func_ptr_ty, data_ptr_ty];
+        let entry_ty = StructType::new(ctx, &entry_fields, false);
+        let make_entry = |priority: &Value, func: LLVMValueRef, data: &Value| -> LLVMValueRef {
+            let func_ptr = unsafe { core::LLVMConstPointerCast(func, func_ptr_ty.into()) };
+            let data_ptr = unsafe { core::LLVMConstPointerCast(data.into(), data_ptr_ty.into()) };
+            let fields = [priority, func_ptr.into(), data_ptr.into()];
+            Value::new_struct(ctx, &fields, false).into()
+        };
+        let entry = make_entry(priority.compile(ctx), func.into(), Value::new_null(data_ptr_ty));
+        // Each entry is kept with its priority, because LLVM folds zeroed entries into a
+        // constant that has no operands.
+        let mut entries: Vec<(u64, LLVMValueRef)> = Vec::new();
+        let old = util::with_cstr(name, |ptr| unsafe {
+            core::LLVMGetNamedGlobal(self.into(), ptr)
+        });
+        unsafe {
+            let init = if old.is_null() { old } else { core::LLVMGetInitializer(old) };
+            if !init.is_null() {
+                let is_zero = |value| !core::LLVMIsAConstantAggregateZero(value).is_null();
+                let count = core::LLVMGetArrayLength(core::LLVMTypeOf(init));
+                // Entries from older IR may only have the priority and function fields, so
+                // they are rebuilt to match the element type of the new array.
+                for i in 0..count {
+                    let old_entry = if is_zero(init) {
+                        init
+                    } else {
+                        core::LLVMGetOperand(init, i)
+                    };
+                    if is_zero(old_entry) {
+                        entries.push((0, Value::new_null(entry_ty).into()));
+                        continue;
+                    }
+                    let data = match core::LLVMGetNumOperands(old_entry) {
+                        2 => Value::new_null(data_ptr_ty),
+                        3 => core::LLVMGetOperand(old_entry, 2).into(),
+                        _ => panic!("{} has an entry with an unknown layout", name),
+                    };
+                    let old_priority = core::LLVMGetOperand(old_entry, 0);
+                    let old_func = core::LLVMGetOperand(old_entry, 1);
+                    let old_entry = make_entry(old_priority.into(), old_func, data);
+                    entries.push((core::LLVMConstIntGetZExtValue(old_priority), old_entry));
+                }
+            }
+        }
+        // The execution engine runs these in order and ignores the priorities, so the
+        // entries are kept sorted by priority.
+        let position = entries
+            .iter()
+            .position(|&(old_priority, _)| old_priority > priority as u64)
+            .unwrap_or(entries.len());
+        entries.insert(position, (priority as u64, entry));
+        let mut entries: Vec<_> = entries.into_iter().map(|(_, entry)| entry).collect();
+        let array = unsafe {
+            core::LLVMConstArray(entry_ty.into(), entries.as_mut_ptr(), entries.len() as c_uint)
+        };
+        let array_ty = ArrayType::new(entry_ty, entries.len());
+        let global = self.add_global(if old.is_null() { name } else { "" }, array_ty);
+        global.set_initializer(array.into());
+        global.set_linkage(Linkage::Appending);
+        if !old.is_null() {
+            // The array's type changes with its length, so the old global is replaced with
+            // the new one. It is only unlinked from the module rather than deleted, so
+            // references to it that were handed out before stay valid.
+            unsafe {
+                let old_ty = core::LLVMTypeOf(old);
+                core::LLVMReplaceAllUsesWith(old, core::LLVMConstBitCast(global.into(), old_ty));
+                core::LLVMSetInitializer(old, ptr::null_mut());
+                ffi_ext::LLVMRsRemoveGlobalFromParent(old);
+            }
+            global.set_name(name);
+        }
+    }
+    /// Append the metadata node given to the named metadata with the name given, creating it
+    /// if it doesn't exist yet.
+    pub fn add_named_metadata_operand(&self, name: &str, node: &MDNode) {
+        util::with_cstr(name, |ptr| unsafe {
+            core::LLVMAddNamedMetadataOperand(self.into(), ptr, node.into())
+        })
+    }
+    /// Returns the metadata nodes in the named metadata with the name given, which is empty if
+    /// there's no named metadata with that name.
+    pub fn get_named_metadata(&self, name: &str) -> Vec<&MDNode> {
+        util::with_cstr(name, |ptr| unsafe {
+            let count = core::LLVMGetNamedMetadataNumOperands(self.into(), ptr) as usize;
+            let mut nodes = vec![ptr::null_mut(); count];
+            core::LLVMGetNamedMetadataOperands(self.into(), ptr, nodes.as_mut_ptr());
+            nodes.into_iter().map(|node| node.into()).collect()
+        })
+    }
+    /// Add a module flag with the key and value given, like `PIC Level` or `wchar_size`.
+    ///
+    /// The behaviour decides what happens when modules that both have this flag are linked
+    /// together.
+    pub fn add_flag(&self, behavior: ModuleFlagBehavior, key: &str, value: &Value) {
+        unsafe {
+            ffi_ext::LLVMAddModuleFlag(
+                self.into(),
+                behavior.into(),
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                core::LLVMValueAsMetadata(value.into()),
+            )
+        }
+    }
+    /// Returns the value of the module flag with the key given, or `None` if there's no such
+    /// flag.
+    pub fn get_flag(&self, key: &str) -> Option<&Value> {
+        unsafe {
+            let key_ptr = key.as_ptr() as *const c_char;
+            let md = ffi_ext::LLVMGetModuleFlag(self.into(), key_ptr, key.len() as size_t);
+            if md.is_null() {
+                return None;
+            }
+            let value = core::LLVMMetadataAsValue(self.get_context().into(), md);
+            if ffi_ext::LLVMGetMetadataKind(md) == ffi_ext::LLVMConstantAsMetadataMetadataKind {
+                // Constants are wrapped in metadata, so unwrap them again.
+                let mut constant = ptr::null_mut();
+                core::LLVMGetMDNodeOperands(value, &mut constant);
+                Some(constant.into())
+            } else {
+                Some(value.into())
+            }
+        }
+    }
+    /// Add a line to the `llvm.ident` metadata, which names the tools that produced this
+    /// module.
+    pub fn add_ident(&self, ident: &str) {
+        let context = self.get_context();
+        let node = MDNode::new(context, &[MDString::new(context, ident)]);
+        self.add_named_metadata_operand("llvm.ident", node);
+    }
+    /// Returns the lines in the `llvm.ident` metadata, or an error if one isn't valid UTF-8.
+    pub fn get_idents(&self) -> Result<Vec<&str>, Utf8Error> {
+        self.get_named_metadata("llvm.ident")
+            .into_iter()
+            .filter_map(|node| node.get_operands().into_iter().next())
+            .filter_map(|ident| ident.and_then(MDString::from_super))
+            .map(|ident| ident.get_string())
+            .collect()
+    }
+    /// Iterate through the global variables in this module.
+    pub fn globals(&self) -> Globals {
+        Globals::new(unsafe { core::LLVMGetFirstGlobal(self.into()) })
+    }
+    /// Iterate through the aliases in this module.
+    pub fn aliases(&self) -> Aliases {
+        Aliases::new(unsafe { ffi_ext::LLVMGetFirstGlobalAlias(self.into()) })
+    }
+    /// Iterate through the named metadata in this module.
+    pub fn named_metadata(&self) -> NamedMetadatas {
+        NamedMetadatas::new(unsafe { ffi_ext::LLVMGetFirstNamedMetadata(self.into()) })
+    }
+    /// Iterate through the identified (non-literal) struct types used by this module, in the
+    /// order they're first used.
+    ///
+    /// LLVM doesn't keep a list of these, so this walks the types of every global, function,
+    /// instruction and constant in the module to find them.
+    pub fn struct_types(&self) -> StructTypes {
+        let mut finder = TypeFinder {
+            types: HashSet::new(),
+            values: HashSet::new(),
+            structs: Vec::new(),
+        };
+        unsafe {
+            let mut global = core::LLVMGetFirstGlobal(self.into());
+            while !global.is_null() {
+                finder.add_value(global);
+                global = core::LLVMGetNextGlobal(global);
+            }
+            let mut alias = ffi_ext::LLVMGetFirstGlobalAlias(self.into());
+            while !alias.is_null() {
+                finder.add_value(alias);
+                alias = ffi_ext::LLVMGetNextGlobalAlias(alias);
+            }
+            let mut func = core::LLVMGetFirstFunction(self.into());
+            while !func.is_null() {
+                finder.add_value(func);
+                let mut block = core::LLVMGetFirstBasicBlock(func);
+                while !block.is_null() {
+                    let mut instr = core::LLVMGetFirstInstruction(block);
+                    while !instr.is_null() {
+                        finder.add_value(instr);
+                        instr = core::LLVMGetNextInstruction(instr);
+                    }
+                    block = core::LLVMGetNextBasicBlock(block);
+                }
+                func = core::LLVMGetNextFunction(func);
+            }
+        }
+        StructTypes {
+            iter: finder.structs.into_iter(),
+            marker: PhantomData,
+        }
+    }
     /// Parse this bitcode file into a module, or return an error string.
-    pub fn parse_bitcode<'a>(context: &'a Context, path: &str) -> Option<CSemiBox<'a, Module>> {
+    pub fn parse_bitcode<'a>(
+        context: &'a Context,
+        path: &str,
+    ) -> Result<CSemiBox<'a, Module>, CBox<str>> {
+        let buf = try!(MemoryBuffer::new_from_file(path));
+        Module::parse_bitcode_from_buffer(context, &buf)
+    }
+    /// Parse the bitcode given into a module, or return an error string.
+    pub fn parse_bitcode_from_bytes<'a>(
+        context: &'a Context,
+        bitcode: &[u8],
+    ) -> Result<CSemiBox<'a, Module>, CBox<str>> {
+        let buf = MemoryBuffer::new_from_slice(bitcode, None);
+        Module::parse_bitcode_from_buffer(context, &buf)
+    }
+    /// Parse the bitcode in the buffer given into a module, or return an error string.
+    // The newer `LLVMParseBitcodeInContext2` doesn't return an error message.
+    #[allow(deprecated)]
+    pub fn parse_bitcode_from_buffer<'a>(
+        context: &'a Context,
+        buf: &MemoryBuffer,
+    ) -> Result<CSemiBox<'a, Module>, CBox<str>> {
         unsafe {
-            let mut out = mem::uninitialized();
-            let buf = MemoryBuffer::new_from_file(path).unwrap();
-            if reader::LLVMParseBitcodeInContext2(context.into(), buf.as_ptr(), &mut out) == 1 {
-                None
+            let mut out = ptr::null_mut();
+            let mut err = ptr::null_mut();
+            if reader::LLVMParseBitcodeInContext(context.into(), buf.into(), &mut out, &mut err)
+                == 1
+            {
+                Err(CBox::new(err))
             } else {
-                Some(CSemiBox::new(out))
+                Ok(CSemiBox::new(out))
             }
         }
     }
+    /// Lazily load the bitcode in the buffer given into a module, or return an error string.
+    ///
+    /// The module takes ownership of the buffer, and the bodies of its functions are only
+    /// read from it when they are materialized with `GlobalValue::materialize` or
+    /// `Module::materialize_all`. A `JitEngine` materializes every function in the module
+    /// when it compiles it.
+    #[allow(deprecated)]
+    pub fn get_lazy_bitcode_module<'a>(
+        context: &'a Context,
+        buf: CSemiBox<'static, MemoryBuffer>,
+    ) -> Result<CSemiBox<'a, Module>, CBox<str>> {
+        unsafe {
+            let mut out = ptr::null_mut();
+            let mut err = ptr::null_mut();
+            // `LLVMGetBitcodeModuleInContext2` reports errors through the context's
+            // diagnostic handler, which exits the process by default, so this uses the
+            // older function that returns an error message instead.
+            let buf = buf.unwrap();
+            if reader::LLVMGetBitcodeModuleInContext(context.into(), buf, &mut out, &mut err) == 1 {
+                // The module only takes ownership of the buffer when it was made.
+                core::LLVMDisposeMemoryBuffer(buf);
+                Err(CBox::new(err))
+            } else {
+                Ok(CSemiBox::new(out))
+            }
+        }
+    }
+    /// Read the bodies of all the lazily loaded globals in this module, or return an error
+    /// string.
+    pub fn materialize_all(&self) -> Result<(), CBox<str>> {
+        unsafe {
+            let mut err = ptr::null_mut();
+            if ffi_ext::LLVMRsMaterializeAll(self.into(), &mut err) == 1 {
+                Err(CBox::new(err))
+            } else {
+                Ok(())
+            }
+        }
+    }
+    /// Returns a buffer containing this module's bitcode.
+    pub fn to_bitcode_buffer(&self) -> CSemiBox<'static, MemoryBuffer> {
+        unsafe { CSemiBox::new(writer::LLVMWriteBitcodeToMemoryBuffer(self.into())) }
+    }
+    /// Returns this module's bitcode.
+    pub fn to_bitcode(&self) -> Vec<u8> {
+        self.to_bitcode_buffer().as_bytes().to_vec()
+    }
+    /// Write this module's bitcode to the writer given.
+    pub fn write_bitcode_to<W: Write>(&self, out: &mut W) -> IoResult<()> {
+        out.write_all(&self.to_bitcode())
+    }
     /// Write this module's bitcode to the path given.
     pub fn write_bitcode(&self, path: &str) -> IoResult<()> {
         util::with_cstr(path, |cpath| unsafe {
@@ -110,22 +431,64 @@ impl Module {
             }
         })
     }
-    /// Parse IR assembly unto a module, or return an error string.
-    pub fn parse_ir_from_str<'a>(
-        context: &'a Context,
-        s: &str,
-    ) -> Result<CSemiBox<'a, Module>, CBox<str>> {
-        unsafe {
-            let mut out = mem::uninitialized();
-            let mut err = mem::uninitialized();
-            let buf = try!(MemoryBuffer::new_from_str(s, None));
-            if ir_reader::LLVMParseIRInContext(context.into(), buf.as_ptr(), &mut out, &mut err)
-                == 1
-            {
+    /// Print the IR assembly of this module to the path given, or return an error string.
+    pub fn print_to_file(&self, path: &str) -> Result<(), CBox<str>> {
+        util::with_cstr(path, |path| unsafe {
+            let mut err = ptr::null_mut();
+            if core::LLVMPrintModuleToFile(self.into(), path, &mut err) == 1 {
                 Err(CBox::new(err))
             } else {
-                Ok(CSemiBox::new(out))
+                Ok(())
             }
+        })
+    }
+    /// Write the IR assembly of this module to the writer given.
+    pub fn write_ir<W: Write>(&self, out: &mut W) -> IoResult<()> {
+        unsafe {
+            let c_str = core::LLVMPrintModuleToString(self.into());
+            let result = out.write_all(CStr::from_ptr(c_str).to_bytes());
+            core::LLVMDisposeMessage(c_str);
+            result
+        }
+    }
+    /// Parse IR assembly unto a module, or return the error that stopped it.
+    pub fn parse_ir_from_str<'a>(
+        context: &'a Context,
+        s: &str,
+    ) -> Result<CSemiBox<'a, Module>, IrError> {
+        let buf = MemoryBuffer::new_from_str(s, Some("<string>")).unwrap();
+        unsafe { Module::parse_ir_buffer(context, buf) }
+    }
+    /// Parse the IR assembly file at the path given into a module, or return the error that
+    /// stopped it.
+    pub fn parse_ir_from_file<'a>(
+        context: &'a Context,
+        path: &str,
+    ) -> Result<CSemiBox<'a, Module>, IrError> {
+        match MemoryBuffer::new_from_file(path) {
+            Ok(buf) => unsafe { Module::parse_ir_buffer(context, buf) },
+            Err(err) => Err(IrError {
+                file: path.to_owned(),
+                line: None,
+                column: None,
+                message: err.to_string(),
+                source_line: None,
+            }),
+        }
+    }
+    /// Parse the IR assembly in the buffer given, which the parser takes ownership of.
+    unsafe fn parse_ir_buffer<'a>(
+        context: &'a Context,
+        buf: CSemiBox<'static, MemoryBuffer>,
+    ) -> Result<CSemiBox<'a, Module>, IrError> {
+        let mut out = ptr::null_mut();
+        let mut err = ptr::null_mut();
+        if ir_reader::LLVMParseIRInContext(context.into(), buf.unwrap(), &mut out, &mut err) == 1 {
+            let error = IrError::parse(util::to_str(err));
+            core::LLVMDisposeMessage(err);
+            Err(error)
+        } else {
+            Ok(CSemiBox::new(out))
         }
     }
     /// Add a function to the module with the name given.
@@ -196,6 +559,63 @@ impl Module {
         unsafe { core::LLVMSetTarget(self.into(), c_target.as_ptr()) }
     }
 
+    /// Returns the target triple of this module.
+    pub fn get_triple(&self) -> Triple {
+        Triple::new(self.get_target())
+    }
+
+    /// Set the target triple of this module.
+    pub fn set_triple(&self, triple: &Triple) {
+        self.set_target(&triple.to_string())
+    }
+
+    /// Returns the data layout of this module represented as a string.
+    ///
+    /// This is copied out of the module because `set_data_layout` replaces LLVM's copy.
+    pub fn get_data_layout(&self) -> String {
+        unsafe {
+            let layout = core::LLVMGetDataLayoutStr(self.into());
+            util::to_str(layout as *mut c_char).to_owned()
+        }
+    }
+
+    /// Set the data layout of this module to the target data given.
+    pub fn set_data_layout(&self, data: &TargetData) {
+        unsafe { target::LLVMSetModuleDataLayout(self.into(), data.into()) }
+    }
+
+    /// Set the target triple and data layout of this module to those of the target machine
+    /// given, which the optimizer and code generator rely on being correct.
+    pub fn configure_for(&self, machine: &TargetMachine) {
+        self.set_triple(&machine.get_triple());
+        self.set_data_layout(&machine.target_data());
+    }
+
+    /// Set the module-level assembly of this module, which is emitted as-is at the top of
+    /// its assembly file.
+    pub fn set_inline_asm(&self, asm: &str) {
+        let ptr = asm.as_ptr() as *const c_char;
+        unsafe { ffi_ext::LLVMSetModuleInlineAsm2(self.into(), ptr, asm.len()) }
+    }
+
+    /// Append the text given to the module-level assembly of this module, on a new line.
+    pub fn append_inline_asm(&self, asm: &str) {
+        let ptr = asm.as_ptr() as *const c_char;
+        unsafe { ffi_ext::LLVMAppendModuleInlineAsm(self.into(), ptr, asm.len()) }
+    }
+
+    /// Returns the module-level assembly of this module, or an error if it is not valid UTF-8.
+    ///
+    /// This is copied out of the module because `set_inline_asm` and `append_inline_asm`
+    /// replace LLVM's copy.
+    pub fn get_inline_asm(&self) -> Result<String, Utf8Error> {
+        unsafe {
+            let mut len = 0;
+            let asm = ffi_ext::LLVMGetModuleInlineAsm(self.into(), &mut len);
+            str::from_utf8(slice::from_raw_parts(asm as *const u8, len)).map(str::to_owned)
+        }
+    }
+
     /// Verify that the module is safe to run, returning a string detailing the error
     /// when an error occurs.
     pub fn verify(&self) -> Result<(), CBox<str>> {
@@ -249,38 +669,203 @@ impl<'a> IntoIterator for &'a Module {
     type IntoIter = Functions<'a>;
     /// Iterate through the functions in the module
     fn into_iter(self) -> Functions<'a> {
-        Functions {
-            index: 0,
-            value: unsafe { core::LLVMGetFirstFunction(self.into()) },
-            marker: PhantomData,
-        }
+        Functions::new(unsafe { core::LLVMGetFirstFunction(self.into()) })
     }
 }
 get_context!(Module, LLVMGetModuleContext);
 to_str!(Module, LLVMPrintModuleToString);
 dispose!(Module, LLVMModule, core::LLVMDisposeModule);
-#[derive(Copy, Clone)]
-/// An iterator through the functions contained in a module.
-pub struct Functions<'a> {
-    index: usize,
-    value: LLVMValueRef,
+
+macro_rules! module_iter(
+    ($(#[$attr:meta])* $name:ident, $item:ty, $ref_ty:ty, $next:path) => (
+        $(#[$attr])*
+        #[derive(Copy, Clone)]
+        pub struct $name<'a> {
+            value: $ref_ty,
+            marker: PhantomData<&'a ()>,
+        }
+        impl<'a> $name<'a> {
+            fn new(first: $ref_ty) -> $name<'a> {
+                $name {
+                    value: first,
+                    marker: PhantomData,
+                }
+            }
+        }
+        impl<'a> Iterator for $name<'a> {
+            type Item = &'a $item;
+            fn next(&mut self) -> Option<&'a $item> {
+                if self.value.is_null() {
+                    None
+                } else {
+                    let item = self.value;
+                    self.value = unsafe { $next(item) };
+                    Some(item.into())
+                }
+            }
+        }
+    );
+);
+module_iter!{
+    /// An iterator through the functions contained in a module.
+    Functions, Function, LLVMValueRef, core::LLVMGetNextFunction
+}
+module_iter!{
+    /// An iterator through the global variables contained in a module.
+    Globals, GlobalVariable, LLVMValueRef, core::LLVMGetNextGlobal
+}
+module_iter!{
+    /// An iterator through the aliases contained in a module.
+    Aliases, Alias, LLVMValueRef, ffi_ext::LLVMGetNextGlobalAlias
+}
+module_iter!{
+    /// An iterator through the named metadata contained in a module.
+    NamedMetadatas, NamedMetadata, LLVMNamedMDNodeRef, ffi_ext::LLVMGetNextNamedMetadata
+}
+
+/// An iterator through the identified struct types used by a module.
+pub struct StructTypes<'a> {
+    iter: vec::IntoIter<LLVMTypeRef>,
     marker: PhantomData<&'a ()>,
 }
-impl<'a> Iterator for Functions<'a> {
-    type Item = &'a Function;
-    fn next(&mut self) -> Option<&'a Function> {
-        let o = if self.value.is_null() {
-            None
-        } else if self.index == 0 {
-            Some(self.value.into())
-        } else {
-            let c_next = unsafe { core::LLVMGetNextFunction(self.value) };
-            self.value = c_next;
-            Some(self.value.into())
+impl<'a> Iterator for StructTypes<'a> {
+    type Item = &'a StructType;
+    fn next(&mut self) -> Option<&'a StructType> {
+        self.iter.next().map(|ty| ty.into())
+    }
+}
+
+/// Collects the identified struct types reachable from a set of values.
+struct TypeFinder {
+    types: HashSet<LLVMTypeRef>,
+    values: HashSet<LLVMValueRef>,
+    structs: Vec<LLVMTypeRef>,
+}
+impl TypeFinder {
+    unsafe fn add_value(&mut self, value: LLVMValueRef) {
+        if !self.values.insert(value) {
+            return;
+        }
+        self.add_type(core::LLVMTypeOf(value));
+        let is_user = !core::LLVMIsAInstruction(value).is_null()
+            || (!core::LLVMIsAConstant(value).is_null()
+                && core::LLVMIsAGlobalValue(value).is_null());
+        if is_user {
+            for index in 0..core::LLVMGetNumOperands(value) {
+                let operand = core::LLVMGetOperand(value, index as c_uint);
+                if !operand.is_null() {
+                    self.add_value(operand);
+                }
+            }
+        }
+        if !core::LLVMIsAGlobalVariable(value).is_null() {
+            let init = core::LLVMGetInitializer(value);
+            if !init.is_null() {
+                self.add_value(init);
+            }
+        }
+    }
+    unsafe fn add_type(&mut self, ty: LLVMTypeRef) {
+        if !self.types.insert(ty) {
+            return;
+        }
+        if core::LLVMGetTypeKind(ty) == LLVMTypeKind::LLVMStructTypeKind
+            && ffi_ext::LLVMIsLiteralStruct(ty) == 0
+        {
+            self.structs.push(ty);
+        }
+        let count = core::LLVMGetNumContainedTypes(ty) as usize;
+        let mut subtypes = vec![ptr::null_mut(); count];
+        core::LLVMGetSubtypes(ty, subtypes.as_mut_ptr());
+        for subtype in subtypes {
+            self.add_type(subtype);
+        }
+    }
+}
+
+/// An error from parsing IR assembly.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IrError {
+    /// The name of the file the error is in.
+    pub file: String,
+    /// The line the error is on, starting at 1.
+    pub line: Option<usize>,
+    /// The column the error is at, starting at 1.
+    pub column: Option<usize>,
+    /// The description of the error.
+    pub message: String,
+    /// The text of the line the error is on.
+    pub source_line: Option<String>,
+}
+impl IrError {
+    /// Parse a diagnostic printed by LLVM, like `file.ll:1:5: error: expected type`.
+    fn parse(text: &str) -> IrError {
+        let mut lines = text.lines();
+        let first = lines.next().unwrap_or("");
+        let mut error = IrError {
+            file: String::new(),
+            line: None,
+            column: None,
+            message: first.trim_start_matches("error: ").to_owned(),
+            source_line: lines.next().map(|line| line.to_owned()),
         };
+        if let Some(index) = first.find(": error: ") {
+            error.message = first[index + 9..].to_owned();
+            let mut location = first[..index].rsplitn(3, ':');
+            let column = location.next().and_then(|col| col.parse().ok());
+            let line = location.next().and_then(|line| line.parse().ok());
+            match (location.next(), line, column) {
+                (Some(file), Some(line), Some(column)) => {
+                    error.file = file.to_owned();
+                    error.line = Some(line);
+                    error.column = Some(column);
+                }
+                _ => error.file = first[..index].to_owned(),
+            }
+        }
+        error
+    }
+}
+impl fmt::Display for IrError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if !self.file.is_empty() {
+            try!(write!(fmt, "{}:", self.file));
+        }
+        if let (Some(line), Some(column)) = (self.line, self.column) {
+            try!(write!(fmt, "{}:{}:", line, column));
+        }
+        if !self.file.is_empty() {
+            try!(write!(fmt, " "));
+        }
+        try!(write!(fmt, "error: {}", self.message));
+        if let Some(ref source_line) = self.source_line {
+            try!(write!(fmt, "\n{}", source_line));
+        }
+        Ok(())
+    }
+}
+impl ::std::error::Error for IrError {}
 
-        self.index += 1;
-        o
+/// What happens when two modules with the same module flag are linked together.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub enum ModuleFlagBehavior {
+    /// Linking fails if the values differ.
+    Error = 0,
+    /// A warning is emitted if the values differ, and the first value is kept.
+    Warning = 1,
+    /// The module must have another flag with the key and value in this flag's value.
+    Require = 2,
+    /// This value wins over any other value, unless both modules override it differently.
+    Override = 3,
+    /// The values, which must be metadata nodes, are appended together.
+    Append = 4,
+    /// Like `Append`, but duplicate values are dropped.
+    AppendUnique = 5,
+}
+impl From<ModuleFlagBehavior> for LLVMModuleFlagBehavior {
+    fn from(behavior: ModuleFlagBehavior) -> LLVMModuleFlagBehavior {
+        unsafe { mem::transmute(behavior) }
     }
 }
 
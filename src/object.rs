@@ -1,10 +1,10 @@
 use libc::c_void;
-use ffi::object::{self, LLVMObjectFileRef, LLVMSymbolIteratorRef};
-use cbox::CBox;
+use ffi::object::{self, LLVMObjectFileRef, LLVMSectionIteratorRef, LLVMSymbolIteratorRef};
+use cbox::{CBox, CSemiBox};
 use std::fmt;
 use std::iter::Iterator;
 use std::marker::PhantomData;
-use std::mem;
+use std::{mem, slice};
 use buffer::MemoryBuffer;
 use util;
 
@@ -17,8 +17,16 @@ impl ObjectFile {
     /// Parse the object file at the path given, or return an error string if an error occurs.
     pub fn read(path: &str) -> Result<ObjectFile, CBox<str>> {
         let buf = try!(MemoryBuffer::new_from_file(path));
+        ObjectFile::from_buffer(buf)
+    }
+    /// Parse the object file in the bytes given, or return an error string if an error occurs.
+    pub fn from_bytes(bytes: &[u8]) -> Result<ObjectFile, CBox<str>> {
+        ObjectFile::from_buffer(MemoryBuffer::new_from_bytes(bytes, None))
+    }
+    /// Parse the object file in the buffer given, which the object file takes ownership of.
+    pub fn from_buffer(buf: CSemiBox<'static, MemoryBuffer>) -> Result<ObjectFile, CBox<str>> {
         unsafe {
-            let ptr = object::LLVMCreateObjectFile(buf.as_ptr());
+            let ptr = object::LLVMCreateObjectFile(buf.unwrap());
             if ptr.is_null() {
                 Err(CBox::from("unknown error"))
             } else {
@@ -29,12 +37,27 @@ impl ObjectFile {
     /// Iterate through the symbols in this object file.
     pub fn symbols(&self) -> Symbols {
         Symbols {
+            obj: self.obj,
             iter: unsafe { object::LLVMGetSymbols(self.obj) },
             marker: PhantomData,
         }
     }
+    /// Iterate through the sections in this object file.
+    pub fn sections(&self) -> Sections {
+        Sections {
+            obj: self.obj,
+            iter: unsafe { object::LLVMGetSections(self.obj) },
+            marker: PhantomData,
+        }
+    }
+}
+impl Drop for ObjectFile {
+    fn drop(&mut self) {
+        unsafe { object::LLVMDisposeObjectFile(self.obj) }
+    }
 }
 pub struct Symbols<'a> {
+    obj: LLVMObjectFileRef,
     iter: LLVMSymbolIteratorRef,
     marker: PhantomData<&'a ()>,
 }
@@ -42,9 +65,13 @@ impl<'a> Iterator for Symbols<'a> {
     type Item = Symbol<'a>;
     fn next(&mut self) -> Option<Symbol<'a>> {
         unsafe {
+            if object::LLVMIsSymbolIteratorAtEnd(self.obj, self.iter) != 0 {
+                return None;
+            }
             let name = util::to_str(object::LLVMGetSymbolName(self.iter) as *mut i8);
             let size = object::LLVMGetSymbolSize(self.iter) as usize;
             let address = object::LLVMGetSymbolAddress(self.iter) as usize;
+            object::LLVMMoveToNextSymbol(self.iter);
             Some(Symbol {
                 name: name,
                 address: mem::transmute(address),
@@ -82,3 +109,57 @@ impl<'a> Symbol<'a> {
         mem::transmute(self.address)
     }
 }
+pub struct Sections<'a> {
+    obj: LLVMObjectFileRef,
+    iter: LLVMSectionIteratorRef,
+    marker: PhantomData<&'a ()>,
+}
+impl<'a> Iterator for Sections<'a> {
+    type Item = Section<'a>;
+    fn next(&mut self) -> Option<Section<'a>> {
+        unsafe {
+            if object::LLVMIsSectionIteratorAtEnd(self.obj, self.iter) != 0 {
+                return None;
+            }
+            // The null section at the start of ELF files has no name.
+            let name = util::to_null_str(object::LLVMGetSectionName(self.iter) as *mut i8);
+            let size = object::LLVMGetSectionSize(self.iter) as usize;
+            let contents = object::LLVMGetSectionContents(self.iter);
+            let address = object::LLVMGetSectionAddress(self.iter);
+            object::LLVMMoveToNextSection(self.iter);
+            Some(Section {
+                name: name.unwrap_or(""),
+                address: address,
+                contents: if contents.is_null() {
+                    &[]
+                } else {
+                    slice::from_raw_parts(contents as *const u8, size)
+                },
+            })
+        }
+    }
+}
+impl<'a> Drop for Sections<'a> {
+    fn drop(&mut self) {
+        unsafe { object::LLVMDisposeSectionIterator(self.iter) }
+    }
+}
+pub struct Section<'a> {
+    /// The name of this section.
+    pub name: &'a str,
+    /// The address this section is loaded at.
+    pub address: u64,
+    /// The contents of this section.
+    pub contents: &'a [u8],
+}
+impl<'a> Copy for Section<'a> {}
+impl<'a> Clone for Section<'a> {
+    fn clone(&self) -> Section<'a> {
+        *self
+    }
+}
+impl<'a> fmt::Debug for Section<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{} - {}", self.name, self.contents.len())
+    }
+}
@@ -1,75 +1,165 @@
 use libc::{c_char, c_uint};
 use ffi::target_machine::*;
 use ffi::target::*;
-use ffi::core::LLVMDisposeMessage;
+use ffi::core::{LLVMDisposeMessage, LLVMIsOpaqueStruct};
+use cbox::CBox;
+use ffi_ext;
 use std::ffi::CStr;
-use std::fmt;
-use types::Type;
+use context::Context;
+use std::{fmt, mem};
+use module::{AddressSpace, Module};
+use types::{StructType, Type};
 use util;
 use std::ptr;
 use pass_manager::PassManager;
 
 /// Represents an LLVM Target
-pub struct TargetData(LLVMTargetDataRef);
-native_ref!(&TargetData = LLVMTargetDataRef);
+pub struct TargetData {
+    data: LLVMTargetDataRef,
+}
+native_ref!(TargetData, data: LLVMTargetDataRef);
 
 impl TargetData {
     /// Create a target data from a target layout string.
     pub fn from_string(rep: &str) -> TargetData {
-        TargetData(unsafe { LLVMCreateTargetData(rep.as_ptr() as *const c_char) })
+        util::with_cstr(rep, |rep| TargetData {
+            data: unsafe { LLVMCreateTargetData(rep) },
+        })
+    }
+
+    /// Create a target data from the data layout of the module given.
+    ///
+    /// This is a copy, so it stays valid even if the module's layout changes.
+    pub fn from_module(module: &Module) -> TargetData {
+        TargetData::from_string(&module.get_data_layout())
     }
 
     /// Returns true if the target is big endian.
     pub fn is_big_endian(&self) -> bool {
-        let order = unsafe { LLVMByteOrder(self.0) } as c_uint;
+        let order = unsafe { LLVMByteOrder(self.data) } as c_uint;
         order == 0
     }
     /// Returns the size of a pointer on the target.
     pub fn get_pointer_size(&self) -> usize {
-        unsafe { LLVMPointerSize(self.0) as usize }
+        unsafe { LLVMPointerSize(self.data) as usize }
+    }
+    /// Returns the size of a pointer in the address space given on the target.
+    pub fn get_pointer_size_in(&self, address: AddressSpace) -> usize {
+        unsafe { LLVMPointerSizeForAS(self.data, address as c_uint) as usize }
+    }
+    /// Returns the integer type that is the same size as a pointer on the target.
+    pub fn get_int_ptr_type<'a>(&self, context: &'a Context) -> &'a Type {
+        unsafe { LLVMIntPtrTypeInContext(context.into(), self.data).into() }
+    }
+    /// Returns the integer type that is the same size as a pointer in the address space given
+    /// on the target.
+    pub fn get_int_ptr_type_in<'a>(&self, context: &'a Context, address: AddressSpace) -> &'a Type {
+        unsafe {
+            LLVMIntPtrTypeForASInContext(context.into(), self.data, address as c_uint).into()
+        }
     }
     /// Returns the size of the type given in bits.
     pub fn size_of_in_bits(&self, ty: &Type) -> u64 {
-        unsafe { LLVMSizeOfTypeInBits(self.0, ty.into()) }
+        unsafe { LLVMSizeOfTypeInBits(self.data, ty.into()) }
     }
     /// Returns the size of the type given in bytes.
     pub fn size_of(&self, ty: &Type) -> u64 {
-        unsafe { LLVMStoreSizeOfType(self.0, ty.into()) }
+        unsafe { LLVMStoreSizeOfType(self.data, ty.into()) }
+    }
+    /// Returns the size of the type given in bytes, including the padding added to arrays
+    /// of it, like `sizeof` in C.
+    pub fn abi_size_of(&self, ty: &Type) -> u64 {
+        unsafe { LLVMABISizeOfType(self.data, ty.into()) }
     }
     /// Returns the alignment of the type given in bytes.
     pub fn alignment_of(&self, ty: &Type) -> usize {
-        unsafe { LLVMABIAlignmentOfType(self.0, ty.into()) as usize }
+        unsafe { LLVMABIAlignmentOfType(self.data, ty.into()) as usize }
+    }
+    /// Returns the alignment the target prefers for the type given in bytes.
+    pub fn preferred_alignment_of(&self, ty: &Type) -> usize {
+        unsafe { LLVMPreferredAlignmentOfType(self.data, ty.into()) as usize }
+    }
+    /// Returns the alignment of the type given in bytes when it is on the call stack.
+    pub fn call_frame_alignment_of(&self, ty: &Type) -> usize {
+        unsafe { LLVMCallFrameAlignmentOfType(self.data, ty.into()) as usize }
     }
     /// Computes the structure element that contains the byte offset for a target.
     pub fn element_at(&self, struct_ty: &Type, offset: u64) -> usize {
-        unsafe { LLVMElementAtOffset(self.0, struct_ty.into(), offset) as usize }
+        unsafe { LLVMElementAtOffset(self.data, struct_ty.into(), offset) as usize }
     }
     /// Compute the byte offset of an element in the struct type given.
     pub fn offset_of(&self, struct_ty: &Type, element: usize) -> u64 {
-        unsafe { LLVMOffsetOfElement(self.0, struct_ty.into(), element as c_uint) }
+        unsafe { LLVMOffsetOfElement(self.data, struct_ty.into(), element as c_uint) }
+    }
+    /// Returns the layout of the struct type given, or `None` if it is opaque.
+    pub fn struct_layout(&self, struct_ty: &StructType) -> Option<StructLayout> {
+        if unsafe { LLVMIsOpaqueStruct(struct_ty.into()) } != 0 {
+            return None;
+        }
+        let fields = struct_ty
+            .get_elements()
+            .into_iter()
+            .enumerate()
+            .map(|(index, ty)| FieldLayout {
+                offset: self.offset_of(struct_ty, index),
+                size: self.abi_size_of(ty),
+                alignment: self.alignment_of(ty),
+            })
+            .collect();
+        Some(StructLayout {
+            size: self.abi_size_of(struct_ty),
+            alignment: self.alignment_of(struct_ty),
+            fields: fields,
+        })
     }
     /// Returns the string representation of this target data.
+    ///
+    /// This leaks a copy of the string every time it is called.
+    #[deprecated(note = "leaks memory, use `to_string` or the `Display` impl instead")]
     pub fn as_str(&self) -> &str {
-        unsafe {
-            CStr::from_ptr(LLVMCopyStringRepOfTargetData(self.0))
-                .to_str()
-                .unwrap()
-        }
+        unsafe { util::to_str(LLVMCopyStringRepOfTargetData(self.data)) }
     }
 }
 
 impl fmt::Display for TargetData {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        fmt.write_str(&self.as_str())
+        unsafe {
+            let rep = LLVMCopyStringRepOfTargetData(self.data);
+            let result = fmt.write_str(util::to_str(rep));
+            LLVMDisposeMessage(rep);
+            result
+        }
     }
 }
 
 impl Drop for TargetData {
     fn drop(&mut self) {
-        unsafe { LLVMDisposeTargetData(self.0) }
+        unsafe { LLVMDisposeTargetData(self.data) }
     }
 }
 
+/// The layout of a struct type on a target.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StructLayout {
+    /// The size of the struct in bytes, including its tail padding.
+    pub size: u64,
+    /// The alignment of the struct in bytes.
+    pub alignment: usize,
+    /// The layout of each field of the struct, in order.
+    pub fields: Vec<FieldLayout>,
+}
+
+/// The layout of a field in a struct type on a target.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct FieldLayout {
+    /// The offset of the field from the start of the struct in bytes.
+    pub offset: u64,
+    /// The size of the field in bytes.
+    pub size: u64,
+    /// The alignment of the field in bytes.
+    pub alignment: usize,
+}
+
 pub struct Target(*mut LLVMTarget);
 native_ref!(&Target = LLVMTargetRef);
 
@@ -97,10 +187,88 @@ impl Target {
     }
 }
 
+/// A target triple, like `x86_64-unknown-linux-gnu`, which describes the machine code is
+/// generated for.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Triple {
+    /// The architecture, like `x86_64` or `aarch64`.
+    pub arch: String,
+    /// The vendor, like `pc`, `apple` or `unknown`.
+    pub vendor: String,
+    /// The operating system, like `linux` or `darwin`.
+    pub os: String,
+    /// The environment or ABI, like `gnu` or `msvc`, which may be empty.
+    pub environment: String,
+}
+impl Triple {
+    /// Parse the triple given, after normalizing it into `arch-vendor-os-environment` form.
+    pub fn new(triple: &str) -> Triple {
+        util::with_cstr(triple, |triple| unsafe {
+            let normal = ffi_ext::LLVMNormalizeTargetTriple(triple);
+            let triple = Triple::from_normal(util::to_str(normal));
+            LLVMDisposeMessage(normal);
+            triple
+        })
+    }
+    /// Returns the triple of the machine this is running on.
+    pub fn host() -> Triple {
+        unsafe {
+            let host = LLVMGetDefaultTargetTriple();
+            let triple = Triple::new(util::to_str(host));
+            LLVMDisposeMessage(host);
+            triple
+        }
+    }
+    fn from_normal(triple: &str) -> Triple {
+        let mut parts = triple.splitn(4, '-').map(|part| part.to_owned());
+        Triple {
+            arch: parts.next().unwrap_or_default(),
+            vendor: parts.next().unwrap_or_default(),
+            os: parts.next().unwrap_or_default(),
+            environment: parts.next().unwrap_or_default(),
+        }
+    }
+}
+impl fmt::Display for Triple {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(fmt, "{}-{}-{}", self.arch, self.vendor, self.os));
+        if !self.environment.is_empty() {
+            try!(write!(fmt, "-{}", self.environment));
+        }
+        Ok(())
+    }
+}
+
+/// The kind of file a `TargetMachine` emits.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub enum FileType {
+    /// A textual assembly file.
+    Assembly = 0,
+    /// A native object file.
+    Object = 1,
+}
+impl From<FileType> for LLVMCodeGenFileType {
+    fn from(ty: FileType) -> LLVMCodeGenFileType {
+        unsafe { mem::transmute(ty) }
+    }
+}
+
 pub struct TargetMachine(*mut LLVMOpaqueTargetMachine);
 
 impl TargetMachine {
     pub fn new() -> Result<TargetMachine, String> {
+        unsafe {
+            if LLVM_InitializeNativeTarget() == 1 {
+                return Err("failed to initialize native target".into());
+            }
+            if LLVM_InitializeNativeAsmPrinter() == 1 {
+                return Err("failed to initialize native asm printer".into());
+            }
+            if LLVM_InitializeNativeAsmParser() == 1 {
+                return Err("failed to initialize native asm parser".into());
+            }
+        }
         let triple = unsafe { LLVMGetDefaultTargetTriple() };
 
         let triple_str = unsafe { CStr::from_ptr(triple) }
@@ -127,8 +295,8 @@ impl TargetMachine {
             LLVMCreateTargetMachine(
                 target,
                 triple,
-                "".as_ptr() as *const c_char,
-                "".as_ptr() as *const c_char,
+                b"\0".as_ptr() as *const c_char,
+                b"\0".as_ptr() as *const c_char,
                 LLVMCodeGenOptLevel::LLVMCodeGenLevelDefault,
                 LLVMRelocMode::LLVMRelocPIC,
                 LLVMCodeModel::LLVMCodeModelDefault,
@@ -159,9 +327,46 @@ impl TargetMachine {
         }
     }
 
+    /// Returns the target triple this machine generates code for.
+    pub fn get_triple(&self) -> Triple {
+        unsafe {
+            let triple = LLVMGetTargetMachineTriple(self.0);
+            let parsed = Triple::new(util::to_str(triple));
+            LLVMDisposeMessage(triple);
+            parsed
+        }
+    }
+
+    /// Returns the data layout of the code this machine generates.
+    pub fn target_data(&self) -> TargetData {
+        TargetData {
+            data: unsafe { LLVMCreateTargetDataLayout(self.0) },
+        }
+    }
+
     pub fn analysis_passes(&self, pass_manager: &PassManager) {
         unsafe { LLVMAddAnalysisPasses(self.0, pass_manager.into()) }
     }
+
+    /// Compile the module given and write it to the file at the path given, or return an
+    /// error string if an error occurs.
+    pub fn emit_to_file(&self, module: &Module, path: &str, ty: FileType) -> Result<(), CBox<str>> {
+        util::with_cstr(path, |path| unsafe {
+            let mut error = ptr::null_mut();
+            if LLVMTargetMachineEmitToFile(
+                self.0,
+                module.into(),
+                path as *mut c_char,
+                ty.into(),
+                &mut error,
+            ) == 1
+            {
+                Err(CBox::new(error))
+            } else {
+                Ok(())
+            }
+        })
+    }
 }
 
 impl Drop for TargetMachine {
@@ -1,8 +1,9 @@
 use ffi::prelude::LLVMTypeRef;
 use ffi::{core, target, LLVMTypeKind};
-use libc::{c_int, c_uint};
+use libc::{c_char, c_int, c_uint};
 use compile::Compile;
 use context::{Context, GetContext};
+use ffi_ext;
 use target::TargetData;
 use util::{self, Sub};
 use std::{fmt, mem};
@@ -151,6 +152,15 @@ impl StructType {
             }
         }
     }
+    /// Returns the name of this struct, or `None` if it is a literal struct.
+    pub fn get_name(&self) -> Option<&str> {
+        unsafe { util::to_null_str(core::LLVMGetStructName(self.into()) as *mut c_char) }
+    }
+    /// Returns true if this struct is a literal struct, which is identified by its elements
+    /// rather than by its name.
+    pub fn is_literal(&self) -> bool {
+        unsafe { ffi_ext::LLVMIsLiteralStruct(self.into()) != 0 }
+    }
     /// Returns the elements that make up this struct.
     pub fn get_elements(&self) -> Vec<&Type> {
         unsafe {
@@ -1,14 +1,21 @@
 use libc::{c_char, c_int, c_uint};
 use ffi::prelude::LLVMValueRef;
 use ffi::core;
-use ffi::LLVMLinkage;
+use ffi::{LLVMDLLStorageClass, LLVMIntPredicate, LLVMLinkage, LLVMRealPredicate,
+          LLVMThreadLocalMode, LLVMTypeKind, LLVMVisibility};
 use std::ffi::CString;
-use std::{fmt, mem};
+use std::cmp::Ordering;
+use std::{fmt, mem, ptr};
 use std::ops::{Deref, Index};
 use std::marker::PhantomData;
+use cbox::CBox;
+use attribute::{self, Attribute, AttributeIndex};
 use block::{BasicBlock, BlockIter};
+use comdat::Comdat;
 use context::{Context, GetContext};
-use types::{FunctionType, Type};
+use ffi_ext::{self, LLVMInlineAsmDialect, LLVMUnnamedAddr};
+use metadata::MDNode;
+use types::{ArrayType, FunctionType, StructType, Type, VectorType};
 use util::{self, Sub};
 
 macro_rules! sub {
@@ -35,6 +42,81 @@ impl Deref for $this {
     )
 }
 
+macro_rules! const_op(
+    ($(#[$attr:meta])* $name:ident, $func:ident) => (
+        $(#[$attr])*
+        pub fn $name<'a>(left: &'a Value, right: &'a Value) -> &'a Value {
+            assert_eq!(left.get_type(), right.get_type());
+            let ty = left.get_type();
+            assert!(get_int_width(ty).is_some(), "expected integers, got {:?}", ty);
+            unsafe { core::$func(left.into(), right.into()).into() }
+        }
+    );
+    ($(#[$attr:meta])* $name:ident, $ifunc:ident, $ffunc:ident) => (
+        $(#[$attr])*
+        pub fn $name<'a>(left: &'a Value, right: &'a Value) -> &'a Value {
+            assert_eq!(left.get_type(), right.get_type());
+            unsafe {
+                (if left.get_type().is_integer() {
+                    core::$ifunc
+                } else {
+                    core::$ffunc
+                })(left.into(), right.into()).into()
+            }
+        }
+    );
+);
+macro_rules! const_cast(
+    ($(#[$attr:meta])* $name:ident, $func:ident, |$from:ident, $to:ident| $check:expr,
+     $expected:expr) => (
+        $(#[$attr])*
+        pub fn $name<'a>(value: &'a Value, dest: &'a Type) -> &'a Value {
+            let ($from, $to) = (value.get_type(), dest);
+            assert!($check, "expected {}, got {:?} to {:?}", $expected, $from, $to);
+            unsafe { core::$func(value.into(), dest.into()).into() }
+        }
+    );
+);
+
+/// Returns the number of lanes and the bit width of an integer type or integer vector type.
+fn get_int_width(ty: &Type) -> Option<(usize, usize)> {
+    let (elem, lanes) = match VectorType::from_super(ty) {
+        Some(ty) => (ty.get_element(), ty.get_size()),
+        None => (ty, 1),
+    };
+    if elem.is_integer() {
+        Some((lanes, unsafe { core::LLVMGetIntTypeWidth(elem.into()) } as usize))
+    } else {
+        None
+    }
+}
+/// Compares the widths of two integer types with the same number of lanes.
+fn compare_int_widths(from: &Type, to: &Type) -> Option<Ordering> {
+    match (get_int_width(from), get_int_width(to)) {
+        (Some((from_lanes, from)), Some((to_lanes, to))) if from_lanes == to_lanes => {
+            Some(from.cmp(&to))
+        },
+        _ => None,
+    }
+}
+/// Returns the size in bits of a number or vector of numbers.
+fn get_bit_width(ty: &Type) -> Option<usize> {
+    let (elem, lanes) = match VectorType::from_super(ty) {
+        Some(ty) => (ty.get_element(), ty.get_size()),
+        None => (ty, 1),
+    };
+    let width = match unsafe { core::LLVMGetTypeKind(elem.into()) } {
+        LLVMTypeKind::LLVMIntegerTypeKind => unsafe { core::LLVMGetIntTypeWidth(elem.into()) },
+        LLVMTypeKind::LLVMHalfTypeKind => 16,
+        LLVMTypeKind::LLVMFloatTypeKind => 32,
+        LLVMTypeKind::LLVMDoubleTypeKind => 64,
+        LLVMTypeKind::LLVMX86_FP80TypeKind => 80,
+        LLVMTypeKind::LLVMFP128TypeKind | LLVMTypeKind::LLVMPPC_FP128TypeKind => 128,
+        _ => return None,
+    };
+    Some(width as usize * lanes)
+}
+
 /// A typed value that can be used as an operand in instructions.
 #[derive(Clone)]
 pub struct Value(PhantomData<[u8]>);
@@ -75,6 +157,164 @@ impl Value {
     pub fn new_undef<'a>(ty: &'a Type) -> &'a Value {
         unsafe { core::LLVMGetUndef(ty.into()).into() }
     }
+    /// Create a new constant `getelementptr` expression that computes the address of a
+    /// subelement of the constant pointer `pointer`.
+    pub fn new_gep<'a>(pointer: &'a Value, indices: &[&'a Value]) -> &'a Value {
+        Value::check_gep(pointer, indices);
+        unsafe {
+            core::LLVMConstGEP(
+                pointer.into(),
+                indices.as_ptr() as *mut LLVMValueRef,
+                indices.len() as c_uint,
+            ).into()
+        }
+    }
+    /// Create a new constant `getelementptr inbounds` expression that computes the address of
+    /// a subelement of the constant pointer `pointer`.
+    pub fn new_in_bounds_gep<'a>(pointer: &'a Value, indices: &[&'a Value]) -> &'a Value {
+        Value::check_gep(pointer, indices);
+        unsafe {
+            core::LLVMConstInBoundsGEP(
+                pointer.into(),
+                indices.as_ptr() as *mut LLVMValueRef,
+                indices.len() as c_uint,
+            ).into()
+        }
+    }
+    const_op!{
+        /// Create a new constant expression that adds two numbers.
+        new_add, LLVMConstAdd, LLVMConstFAdd
+    }
+    const_op!{
+        /// Create a new constant expression that subtracts `right` from `left`.
+        new_sub, LLVMConstSub, LLVMConstFSub
+    }
+    const_op!{
+        /// Create a new constant expression that multiplies two numbers.
+        new_mul, LLVMConstMul, LLVMConstFMul
+    }
+    const_op!{
+        /// Create a new constant expression that divides `left` by `right`, treating integers
+        /// as signed.
+        new_div, LLVMConstSDiv, LLVMConstFDiv
+    }
+    const_op!{
+        /// Create a new constant expression that divides `left` by `right`, treating them as
+        /// unsigned integers.
+        new_udiv, LLVMConstUDiv
+    }
+    const_op!{
+        /// Create a new constant expression that computes the remainder of dividing `left` by
+        /// `right`, treating integers as signed.
+        new_rem, LLVMConstSRem, LLVMConstFRem
+    }
+    const_op!{
+        /// Create a new constant expression that computes the bitwise and of two integers.
+        new_and, LLVMConstAnd
+    }
+    const_op!{
+        /// Create a new constant expression that computes the bitwise or of two integers.
+        new_or, LLVMConstOr
+    }
+    const_op!{
+        /// Create a new constant expression that computes the bitwise xor of two integers.
+        new_xor, LLVMConstXor
+    }
+    /// Create a new constant expression that compares two numbers, treating integers as signed.
+    pub fn new_cmp<'a>(a: &'a Value, b: &'a Value, pred: Predicate) -> &'a Value {
+        let (at, bt) = (a.get_type(), b.get_type());
+        assert_eq!(at, bt);
+        unsafe {
+            if at.is_integer() {
+                core::LLVMConstICmp(pred.into(), a.into(), b.into()).into()
+            } else if at.is_float() {
+                core::LLVMConstFCmp(pred.into(), a.into(), b.into()).into()
+            } else {
+                panic!("expected numbers, got {:?}", at)
+            }
+        }
+    }
+    const_cast!{
+        /// Create a new constant expression that reinterprets a value as another type of the
+        /// same size.
+        new_bit_cast, LLVMConstBitCast,
+        |from, to| if from.is_pointer() || to.is_pointer() {
+            from.is_pointer() && to.is_pointer()
+        } else {
+            get_bit_width(from).is_some() && get_bit_width(from) == get_bit_width(to)
+        },
+        "a cast between types of the same size"
+    }
+    const_cast!{
+        /// Create a new constant expression that casts an integer to a pointer.
+        new_int_to_ptr, LLVMConstIntToPtr,
+        |from, to| from.is_integer() && to.is_pointer(), "an integer to a pointer"
+    }
+    const_cast!{
+        /// Create a new constant expression that casts a pointer to an integer.
+        new_ptr_to_int, LLVMConstPtrToInt,
+        |from, to| from.is_pointer() && to.is_integer(), "a pointer to an integer"
+    }
+    const_cast!{
+        /// Create a new constant expression that truncates the high-order bits of an integer.
+        new_trunc, LLVMConstTrunc,
+        |from, to| compare_int_widths(from, to) == Some(Ordering::Greater),
+        "an integer to a narrower integer"
+    }
+    const_cast!{
+        /// Create a new constant expression that zero extends an integer.
+        new_zext, LLVMConstZExt,
+        |from, to| compare_int_widths(from, to) == Some(Ordering::Less),
+        "an integer to a wider integer"
+    }
+    const_cast!{
+        /// Create a new constant expression that sign extends an integer.
+        new_sext, LLVMConstSExt,
+        |from, to| compare_int_widths(from, to) == Some(Ordering::Less),
+        "an integer to a wider integer"
+    }
+    /// Create a new constant expression that extracts an element from an aggregate constant.
+    pub fn new_extract_value(agg: &Value, index: usize) -> &Value {
+        Value::get_aggregate_element(agg, index);
+        let mut index = index as c_uint;
+        unsafe { core::LLVMConstExtractValue(agg.into(), &mut index, 1).into() }
+    }
+    /// Create a new constant expression that replaces an element of an aggregate constant.
+    pub fn new_insert_value<'a>(agg: &'a Value, elem: &'a Value, index: usize) -> &'a Value {
+        assert_eq!(elem.get_type(), Value::get_aggregate_element(agg, index));
+        let mut index = index as c_uint;
+        unsafe { core::LLVMConstInsertValue(agg.into(), elem.into(), &mut index, 1).into() }
+    }
+    /// Create a new constant expression that yields `true_val` if `cond` is `1`, and
+    /// `false_val` otherwise.
+    pub fn new_select<'a>(cond: &'a Value, true_val: &'a Value, false_val: &'a Value) -> &'a Value {
+        let bool_ty = Type::get::<bool>(cond.get_context());
+        assert!(cond.get_type() == bool_ty, "expected a bool condition, got {:?}", cond.get_type());
+        assert_eq!(true_val.get_type(), false_val.get_type());
+        unsafe { core::LLVMConstSelect(cond.into(), true_val.into(), false_val.into()).into() }
+    }
+    /// Panics unless `pointer` is a pointer and every index is an integer.
+    fn check_gep(pointer: &Value, indices: &[&Value]) {
+        let ty = pointer.get_type();
+        assert!(ty.is_pointer(), "expected a pointer, got {:?}", ty);
+        for index in indices {
+            let ty = index.get_type();
+            assert!(ty.is_integer(), "expected an integer index, got {:?}", ty);
+        }
+    }
+    /// Returns the type of the element at `index` in the aggregate constant `agg`, and panics
+    /// if there is no such element.
+    fn get_aggregate_element(agg: &Value, index: usize) -> &Type {
+        let ty = agg.get_type();
+        let elem = if let Some(ty) = StructType::from_super(ty) {
+            ty.get_elements().get(index).cloned()
+        } else if let Some(ty) = ArrayType::from_super(ty) {
+            Some(ty.get_element()).filter(|_| index < ty.get_length())
+        } else {
+            panic!("expected a struct or array, got {:?}", ty)
+        };
+        elem.unwrap_or_else(|| panic!("index {} is out of range for {:?}", index, ty))
+    }
     /// Returns the name of this value, or `None` if it lacks a name
     pub fn get_name(&self) -> Option<&str> {
         unsafe {
@@ -91,6 +331,10 @@ impl Value {
     pub fn get_type(&self) -> &Type {
         unsafe { core::LLVMTypeOf(self.into()) }.into()
     }
+    /// Returns true if anything, like an instruction or another global, uses this value.
+    pub fn has_uses(&self) -> bool {
+        unsafe { !core::LLVMGetFirstUse(self.into()).is_null() }
+    }
 }
 /// Comparative operations on values.
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -102,6 +346,55 @@ pub enum Predicate {
     LessThan,
     LessThanOrEqual,
 }
+impl From<Predicate> for LLVMIntPredicate {
+    fn from(pred: Predicate) -> LLVMIntPredicate {
+        match pred {
+            Predicate::Equal => LLVMIntPredicate::LLVMIntEQ,
+            Predicate::NotEqual => LLVMIntPredicate::LLVMIntNE,
+            Predicate::GreaterThan => LLVMIntPredicate::LLVMIntSGT,
+            Predicate::GreaterThanOrEqual => LLVMIntPredicate::LLVMIntSGE,
+            Predicate::LessThan => LLVMIntPredicate::LLVMIntSLT,
+            Predicate::LessThanOrEqual => LLVMIntPredicate::LLVMIntSLE,
+        }
+    }
+}
+impl From<Predicate> for LLVMRealPredicate {
+    fn from(pred: Predicate) -> LLVMRealPredicate {
+        match pred {
+            Predicate::Equal => LLVMRealPredicate::LLVMRealOEQ,
+            Predicate::NotEqual => LLVMRealPredicate::LLVMRealONE,
+            Predicate::GreaterThan => LLVMRealPredicate::LLVMRealOGT,
+            Predicate::GreaterThanOrEqual => LLVMRealPredicate::LLVMRealOGE,
+            Predicate::LessThan => LLVMRealPredicate::LLVMRealOLT,
+            Predicate::LessThanOrEqual => LLVMRealPredicate::LLVMRealOLE,
+        }
+    }
+}
+
+/// A constant array.
+pub struct ConstantArray(PhantomData<[u8]>);
+native_ref!(&ConstantArray = LLVMValueRef);
+sub!{ConstantArray, LLVMIsAConstantArray}
+to_str!{ConstantArray, LLVMPrintValueToString}
+impl ConstantArray {
+    /// Create a new constant array of the values given, which must all have the type given.
+    ///
+    /// LLVM may fold this into a simpler constant, like `zeroinitializer` or a constant data
+    /// array, so this returns a plain value.
+    pub fn new<'a>(elem_ty: &'a Type, vals: &[&'a Value]) -> &'a Value {
+        for val in vals {
+            assert_eq!(val.get_type(), elem_ty);
+        }
+        unsafe {
+            core::LLVMConstArray(
+                elem_ty.into(),
+                vals.as_ptr() as *mut LLVMValueRef,
+                vals.len() as c_uint,
+            ).into()
+        }
+    }
+}
+
 /// An argument that is passed to a function.
 #[derive(Clone)]
 pub struct Arg(PhantomData<[u8]>);
@@ -109,6 +402,120 @@ native_ref!(&Arg = LLVMValueRef);
 sub!{Arg, LLVMIsAArgument}
 to_str!{Arg, LLVMPrintValueToString}
 
+/// An instruction inside a basic block.
+pub struct Instruction(PhantomData<[u8]>);
+native_ref!(&Instruction = LLVMValueRef);
+sub!{Instruction, LLVMIsAInstruction}
+to_str!{Instruction, LLVMPrintValueToString}
+impl Instruction {
+    /// Returns the basic block that contains this instruction, or `None` if it is not in one.
+    pub fn get_parent(&self) -> Option<&BasicBlock> {
+        unsafe { util::ptr_to_null(core::LLVMGetInstructionParent(self.into())) }
+    }
+    /// Attach the metadata node given to this instruction as the kind given, which comes
+    /// from `Context::get_md_kind_id`.
+    pub fn set_metadata(&self, kind: u32, node: &MDNode) {
+        unsafe { core::LLVMSetMetadata(self.into(), kind, node.into()) }
+    }
+    /// Returns the metadata node of the kind given attached to this instruction, if any.
+    pub fn get_metadata(&self, kind: u32) -> Option<&MDNode> {
+        unsafe { util::ptr_to_null(core::LLVMGetMetadata(self.into(), kind)) }
+    }
+    /// Remove the metadata node of the kind given from this instruction.
+    pub fn remove_metadata(&self, kind: u32) {
+        unsafe { core::LLVMSetMetadata(self.into(), kind, ptr::null_mut()) }
+    }
+}
+
+/// A call instruction, as built by `Builder::build_call`.
+pub struct CallSite(PhantomData<[u8]>);
+native_ref!(&CallSite = LLVMValueRef);
+sub!{CallSite, LLVMIsACallInst}
+to_str!{CallSite, LLVMPrintValueToString}
+impl CallSite {
+    /// Attach `attr` to this call, its return value or one of its arguments.
+    pub fn add_attribute(&self, index: AttributeIndex, attr: &Attribute) {
+        unsafe { core::LLVMAddCallSiteAttribute(self.into(), index.into(), attr.into()) }
+    }
+    /// Returns the attributes attached at `index`.
+    pub fn get_attributes(&self, index: AttributeIndex) -> Vec<&Attribute> {
+        unsafe {
+            let count = core::LLVMGetCallSiteAttributeCount(self.into(), index.into());
+            attribute::collect(count, |ptr| {
+                core::LLVMGetCallSiteAttributes(self.into(), index.into(), ptr)
+            })
+        }
+    }
+    /// Returns the enum or type attribute with the kind name given attached at `index`, or
+    /// `None` if there isn't one.
+    pub fn get_enum_attribute(&self, index: AttributeIndex, kind: &str) -> Option<&Attribute> {
+        let kind = attribute::get_kind_id(kind);
+        unsafe {
+            util::ptr_to_null(core::LLVMGetCallSiteEnumAttribute(self.into(), index.into(), kind))
+        }
+    }
+    /// Returns the string attribute with the key given attached at `index`, or `None` if
+    /// there isn't one.
+    pub fn get_string_attribute(&self, index: AttributeIndex, key: &str) -> Option<&Attribute> {
+        unsafe {
+            let ptr = key.as_ptr() as *const c_char;
+            let len = key.len() as c_uint;
+            let attr = core::LLVMGetCallSiteStringAttribute(self.into(), index.into(), ptr, len);
+            util::ptr_to_null(attr)
+        }
+    }
+    /// Remove the enum or type attribute with the kind name given from `index`.
+    pub fn remove_enum_attribute(&self, index: AttributeIndex, kind: &str) {
+        let kind = attribute::get_kind_id(kind);
+        unsafe { core::LLVMRemoveCallSiteEnumAttribute(self.into(), index.into(), kind) }
+    }
+    /// Remove the string attribute with the key given from `index`.
+    pub fn remove_string_attribute(&self, index: AttributeIndex, key: &str) {
+        unsafe {
+            let ptr = key.as_ptr() as *const c_char;
+            let len = key.len() as c_uint;
+            core::LLVMRemoveCallSiteStringAttribute(self.into(), index.into(), ptr, len)
+        }
+    }
+    /// Returns the function this instruction calls.
+    pub fn get_called_value(&self) -> &Value {
+        unsafe { core::LLVMGetCalledValue(self.into()) }.into()
+    }
+    /// Returns the calling convention this call uses.
+    pub fn get_call_conv(&self) -> CallConv {
+        unsafe { core::LLVMGetInstructionCallConv(self.into()) }.into()
+    }
+    /// Set the calling convention this call uses.
+    ///
+    /// Calling a function with a different convention than its own is undefined behaviour,
+    /// so this checks that they agree in debug mode when the called value is a function.
+    pub fn set_call_conv(&self, conv: CallConv) {
+        if cfg!(debug_assertions) {
+            if let Some(func) = Function::from_super(self.get_called_value()) {
+                assert_eq!(
+                    func.get_call_conv(),
+                    CallConv::from(c_uint::from(conv)),
+                    "call site convention doesn't match {:?}",
+                    func.get_name()
+                );
+            }
+        }
+        unsafe { core::LLVMSetInstructionCallConv(self.into(), conv.into()) }
+    }
+    /// Returns true if this call is marked as a tail call.
+    pub fn is_tail_call(&self) -> bool {
+        unsafe { core::LLVMIsTailCall(self.into()) != 0 }
+    }
+    /// Mark this call as a tail call, or not.
+    ///
+    /// This is only a hint, and the code generator may still emit a normal call. The call is
+    /// only guaranteed to be a tail call when both the caller and callee use the `Tail`
+    /// calling convention.
+    pub fn set_tail_call(&self, is_tail: bool) {
+        unsafe { core::LLVMSetTailCall(self.into(), is_tail as c_int) }
+    }
+}
+
 /// A value with global scope (eg: Function, Alias, Global variable)
 pub struct GlobalValue(PhantomData<[u8]>);
 native_ref!(&GlobalValue = LLVMValueRef);
@@ -125,6 +532,79 @@ impl GlobalValue {
     pub fn get_linkage(&self) -> Linkage {
         unsafe { core::LLVMGetLinkage(self.into()).into() }
     }
+    /// Set the visibility of this global's symbol.
+    pub fn set_visibility(&self, visibility: Visibility) {
+        unsafe { core::LLVMSetVisibility(self.into(), visibility.into()) }
+    }
+    /// Returns the visibility of this global's symbol.
+    pub fn get_visibility(&self) -> Visibility {
+        unsafe { core::LLVMGetVisibility(self.into()).into() }
+    }
+    /// Set how this global is stored in or imported from a Windows DLL.
+    pub fn set_dll_storage_class(&self, class: DLLStorageClass) {
+        unsafe { core::LLVMSetDLLStorageClass(self.into(), class.into()) }
+    }
+    /// Returns how this global is stored in or imported from a Windows DLL.
+    pub fn get_dll_storage_class(&self) -> DLLStorageClass {
+        unsafe { core::LLVMGetDLLStorageClass(self.into()).into() }
+    }
+    /// Set the name of the object file section this global is emitted in.
+    ///
+    /// This will panic if this global is an alias, which doesn't have its own section.
+    pub fn set_section(&self, section: &str) {
+        self.assert_object();
+        util::with_cstr(section, |ptr| unsafe { core::LLVMSetSection(self.into(), ptr) })
+    }
+    /// Returns the name of the object file section this global is emitted in, or `None` if
+    /// it goes in the default section.
+    pub fn get_section(&self) -> Option<&str> {
+        unsafe {
+            let section = core::LLVMGetSection(self.into());
+            util::to_null_str(section as *mut c_char).and_then(|section| {
+                if section.is_empty() {
+                    None
+                } else {
+                    Some(section)
+                }
+            })
+        }
+    }
+    /// Set the alignment of this global in bytes, or 0 to let the target decide.
+    ///
+    /// This will panic if this global is an alias, which doesn't have its own alignment.
+    pub fn set_alignment(&self, align: usize) {
+        self.assert_object();
+        unsafe { core::LLVMSetAlignment(self.into(), align as c_uint) }
+    }
+    /// Returns the alignment of this global in bytes, or 0 if the target decides.
+    ///
+    /// This will panic if this global is an alias.
+    pub fn get_alignment(&self) -> usize {
+        self.assert_object();
+        unsafe { core::LLVMGetAlignment(self.into()) as usize }
+    }
+    /// Set whether the address of this global is significant.
+    pub fn set_unnamed_addr(&self, unnamed_addr: UnnamedAddr) {
+        unsafe { ffi_ext::LLVMSetUnnamedAddress(self.into(), unnamed_addr.into()) }
+    }
+    /// Returns whether the address of this global is significant.
+    pub fn get_unnamed_addr(&self) -> UnnamedAddr {
+        unsafe { ffi_ext::LLVMGetUnnamedAddress(self.into()).into() }
+    }
+    /// Put this global into the COMDAT group given.
+    ///
+    /// This will panic if this global is an alias, which is always in its aliasee's group.
+    pub fn set_comdat(&self, comdat: &Comdat) {
+        self.assert_object();
+        unsafe { ffi_ext::LLVMSetComdat(self.into(), comdat.into()) }
+    }
+    /// Returns the COMDAT group this global is in, or `None` if it isn't in one.
+    ///
+    /// This will panic if this global is an alias.
+    pub fn get_comdat(&self) -> Option<&Comdat> {
+        self.assert_object();
+        unsafe { util::ptr_to_null(ffi_ext::LLVMGetComdat(self.into())) }
+    }
     /// Returns true if this global is a declaration (as opposed to a definition).
     pub fn is_declaration(&self) -> bool {
         unsafe {
@@ -132,6 +612,29 @@ impl GlobalValue {
             core::LLVMIsDeclaration(self.into()) == 1
         }
     }
+    /// Returns true if this global was lazily loaded and its body hasn't been read yet.
+    pub fn is_materializable(&self) -> bool {
+        unsafe { ffi_ext::LLVMRsIsMaterializable(self.into()) != 0 }
+    }
+    /// Read the body of this lazily loaded global, or return an error string.
+    ///
+    /// This does nothing if the body has already been read.
+    pub fn materialize(&self) -> Result<(), CBox<str>> {
+        unsafe {
+            let mut err = ptr::null_mut();
+            if ffi_ext::LLVMRsMaterialize(self.into(), &mut err) == 1 {
+                Err(CBox::new(err))
+            } else {
+                Ok(())
+            }
+        }
+    }
+    /// Panic if this is an alias rather than a function or global variable, because LLVM
+    /// doesn't check this itself.
+    fn assert_object(&self) {
+        let is_object = unsafe { !core::LLVMIsAGlobalObject(self.into()).is_null() };
+        assert!(is_object, "{:?} is not a function or global variable", self);
+    }
 }
 
 /// A global variable
@@ -159,6 +662,42 @@ impl GlobalVariable {
     pub fn get_constant(&self) -> bool {
         unsafe { core::LLVMIsGlobalConstant(self.into()) != 0 }
     }
+    /// Set the thread-local storage model of this global, or make it a normal global with
+    /// `ThreadLocalMode::NotThreadLocal`.
+    pub fn set_thread_local_mode(&self, mode: ThreadLocalMode) {
+        unsafe { core::LLVMSetThreadLocalMode(self.into(), mode.into()) }
+    }
+    /// Returns the thread-local storage model of this global.
+    pub fn get_thread_local_mode(&self) -> ThreadLocalMode {
+        unsafe { core::LLVMGetThreadLocalMode(self.into()).into() }
+    }
+    /// Returns true if each thread has its own copy of this global.
+    pub fn is_thread_local(&self) -> bool {
+        unsafe { core::LLVMIsThreadLocal(self.into()) != 0 }
+    }
+    /// Set whether this global may be initialized outside of this module, so its
+    /// initializer must not be relied on.
+    pub fn set_externally_initialized(&self, is_ext_init: bool) {
+        unsafe { core::LLVMSetExternallyInitialized(self.into(), is_ext_init as c_int) }
+    }
+    /// Returns true if this global may be initialized outside of this module.
+    pub fn is_externally_initialized(&self) -> bool {
+        unsafe { core::LLVMIsExternallyInitialized(self.into()) != 0 }
+    }
+    /// Delete this global, or return an error string if it is still used.
+    ///
+    /// A global without uses isn't referred to by any instruction or other global, so deleting it
+    /// can't leave the module with dangling references.
+    pub fn delete(&self) -> Result<(), CBox<str>> {
+        if self.has_uses() {
+            Err(CBox::from("value still has uses"))
+        } else if unsafe { core::LLVMGetGlobalParent(self.into()) }.is_null() {
+            Err(CBox::from("global has been removed from its module"))
+        } else {
+            unsafe { core::LLVMDeleteGlobal(self.into()) }
+            Ok(())
+        }
+    }
 }
 
 /// An alias to another global value.
@@ -166,6 +705,51 @@ pub struct Alias(PhantomData<[u8]>);
 native_ref!(&Alias = LLVMValueRef);
 sub!{Alias, LLVMIsAGlobalAlias, GlobalValue}
 to_str!{Alias, LLVMPrintValueToString}
+impl Alias {
+    /// Returns the value this alias points to.
+    pub fn get_aliasee(&self) -> &Value {
+        unsafe { ffi_ext::LLVMAliasGetAliasee(self.into()).into() }
+    }
+    /// Make this alias point to the value given, which must have the same type as the alias.
+    pub fn set_aliasee(&self, aliasee: &Value) {
+        unsafe { ffi_ext::LLVMAliasSetAliasee(self.into(), aliasee.into()) }
+    }
+}
+/// A piece of inline assembly, which can be called like a function with `Builder::build_call`.
+pub struct InlineAsm(PhantomData<[u8]>);
+native_ref!(&InlineAsm = LLVMValueRef);
+sub!{InlineAsm, LLVMIsAInlineAsm}
+to_str!{InlineAsm, LLVMPrintValueToString}
+impl InlineAsm {
+    /// Make a new piece of inline assembly with the signature given.
+    ///
+    /// The constraints string describes the outputs, inputs and clobbers of the assembly,
+    /// like `=r,r,~{memory}`. Assembly with side effects won't be removed or reordered even
+    /// if its outputs aren't used, and `align_stack` makes the stack be aligned before it.
+    pub fn new<'a>(
+        sig: &'a FunctionType,
+        asm: &str,
+        constraints: &str,
+        has_side_effects: bool,
+        align_stack: bool,
+        dialect: AsmDialect,
+    ) -> &'a InlineAsm {
+        unsafe {
+            ffi_ext::LLVMGetInlineAsm(
+                sig.into(),
+                asm.as_ptr() as *const c_char,
+                asm.len(),
+                constraints.as_ptr() as *const c_char,
+                constraints.len(),
+                has_side_effects as c_int,
+                align_stack as c_int,
+                dialect.into(),
+                0,
+            ).into()
+        }
+    }
+}
+
 /// A function is a kind of value that can be called and contains blocks of code.
 ///
 /// To get the value of each argument to a function, you can use the index operator.
@@ -213,10 +797,78 @@ impl Function {
             core::LLVMGetElementType(ty).into()
         }
     }
-    /// Delete the function
-    pub fn delete(&self) {
+    /// Attach `attr` to this function, its return value or one of its parameters.
+    pub fn add_attribute(&self, index: AttributeIndex, attr: &Attribute) {
+        unsafe { core::LLVMAddAttributeAtIndex(self.into(), index.into(), attr.into()) }
+    }
+    /// Attach the enum attributes with the kind names given to this function, its return
+    /// value or one of its parameters.
+    pub fn add_attributes(&self, index: AttributeIndex, kinds: &[&str]) {
+        for kind in kinds {
+            self.add_attribute(index, Attribute::new_enum(self.get_context(), kind));
+        }
+    }
+    /// Returns the attributes attached at `index`.
+    pub fn get_attributes(&self, index: AttributeIndex) -> Vec<&Attribute> {
         unsafe {
-            core::LLVMDeleteFunction(self.into());
+            let count = core::LLVMGetAttributeCountAtIndex(self.into(), index.into());
+            attribute::collect(count, |ptr| {
+                core::LLVMGetAttributesAtIndex(self.into(), index.into(), ptr)
+            })
+        }
+    }
+    /// Returns the enum or type attribute with the kind name given attached at `index`, or
+    /// `None` if there isn't one.
+    pub fn get_enum_attribute(&self, index: AttributeIndex, kind: &str) -> Option<&Attribute> {
+        let kind = attribute::get_kind_id(kind);
+        unsafe {
+            util::ptr_to_null(core::LLVMGetEnumAttributeAtIndex(self.into(), index.into(), kind))
+        }
+    }
+    /// Returns the string attribute with the key given attached at `index`, or `None` if
+    /// there isn't one.
+    pub fn get_string_attribute(&self, index: AttributeIndex, key: &str) -> Option<&Attribute> {
+        unsafe {
+            let ptr = key.as_ptr() as *const c_char;
+            let len = key.len() as c_uint;
+            let attr = core::LLVMGetStringAttributeAtIndex(self.into(), index.into(), ptr, len);
+            util::ptr_to_null(attr)
+        }
+    }
+    /// Remove the enum or type attribute with the kind name given from `index`.
+    pub fn remove_enum_attribute(&self, index: AttributeIndex, kind: &str) {
+        let kind = attribute::get_kind_id(kind);
+        unsafe { core::LLVMRemoveEnumAttributeAtIndex(self.into(), index.into(), kind) }
+    }
+    /// Remove the string attribute with the key given from `index`.
+    pub fn remove_string_attribute(&self, index: AttributeIndex, key: &str) {
+        unsafe {
+            let ptr = key.as_ptr() as *const c_char;
+            let len = key.len() as c_uint;
+            core::LLVMRemoveStringAttributeAtIndex(self.into(), index.into(), ptr, len)
+        }
+    }
+    /// Returns the calling convention of this function.
+    pub fn get_call_conv(&self) -> CallConv {
+        unsafe { core::LLVMGetFunctionCallConv(self.into()) }.into()
+    }
+    /// Set the calling convention of this function.
+    ///
+    /// Calls to this function built with `Builder::build_call` after this will use the same
+    /// calling convention.
+    pub fn set_call_conv(&self, conv: CallConv) {
+        unsafe { core::LLVMSetFunctionCallConv(self.into(), conv.into()) }
+    }
+    /// Delete this function, or return an error string if it is still used.
+    ///
+    /// A function without uses isn't referred to by any instruction or other global, so deleting it
+    /// can't leave the module with dangling references.
+    pub fn delete(&self) -> Result<(), CBox<str>> {
+        if self.has_uses() {
+            Err(CBox::from("value still has uses"))
+        } else {
+            unsafe { core::LLVMDeleteFunction(self.into()) }
+            Ok(())
         }
     }
 }
@@ -254,6 +906,219 @@ pub enum Linkage {
     /// Similar to Weak, but may not have an explicit section, must have a zero initializer, and may not be marked constant. Cannot be used on functions or aliases.
     Common = 14,
 }
+/// How a global's symbol is visible outside of the shared object it's linked into.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub enum Visibility {
+    /// The symbol is visible to other modules, and may be overridden by them.
+    Default = 0,
+    /// The symbol isn't visible outside of the shared object it's linked into.
+    Hidden = 1,
+    /// The symbol is visible to other modules, but can't be overridden by them.
+    Protected = 2,
+}
+impl From<LLVMVisibility> for Visibility {
+    fn from(attr: LLVMVisibility) -> Visibility {
+        unsafe { mem::transmute(attr) }
+    }
+}
+impl From<Visibility> for LLVMVisibility {
+    fn from(attr: Visibility) -> LLVMVisibility {
+        unsafe { mem::transmute(attr) }
+    }
+}
+
+/// How a global is stored in or imported from a DLL on Windows.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub enum DLLStorageClass {
+    /// The global is neither imported from nor exported by a DLL.
+    Default = 0,
+    /// The global is imported from a DLL.
+    DLLImport = 1,
+    /// The global is exported by the DLL it's linked into.
+    DLLExport = 2,
+}
+impl From<LLVMDLLStorageClass> for DLLStorageClass {
+    fn from(attr: LLVMDLLStorageClass) -> DLLStorageClass {
+        unsafe { mem::transmute(attr) }
+    }
+}
+impl From<DLLStorageClass> for LLVMDLLStorageClass {
+    fn from(attr: DLLStorageClass) -> LLVMDLLStorageClass {
+        unsafe { mem::transmute(attr) }
+    }
+}
+
+/// Whether the address of a global is significant, or just its contents.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub enum UnnamedAddr {
+    /// The address is significant.
+    None = 0,
+    /// The address isn't significant within this module, so it may be merged with other
+    /// globals in it.
+    Local = 1,
+    /// The address isn't significant at all, so it may be merged with any other global.
+    Global = 2,
+}
+impl From<LLVMUnnamedAddr> for UnnamedAddr {
+    fn from(attr: LLVMUnnamedAddr) -> UnnamedAddr {
+        unsafe { mem::transmute(attr) }
+    }
+}
+impl From<UnnamedAddr> for LLVMUnnamedAddr {
+    fn from(attr: UnnamedAddr) -> LLVMUnnamedAddr {
+        unsafe { mem::transmute(attr) }
+    }
+}
+
+/// The syntax of a piece of inline assembly.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub enum AsmDialect {
+    /// AT&T syntax, like `movq %rsp, %rax`.
+    Att = 0,
+    /// Intel syntax, like `mov rax, rsp`.
+    Intel = 1,
+}
+impl From<AsmDialect> for LLVMInlineAsmDialect {
+    fn from(dialect: AsmDialect) -> LLVMInlineAsmDialect {
+        unsafe { mem::transmute(dialect) }
+    }
+}
+
+/// The model used to access a thread-local global.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub enum ThreadLocalMode {
+    /// The global isn't thread-local.
+    NotThreadLocal = 0,
+    /// The most general model, which works everywhere.
+    GeneralDynamic = 1,
+    /// For globals that are only used within the shared object they're defined in.
+    LocalDynamic = 2,
+    /// For globals in modules that won't be loaded dynamically.
+    InitialExec = 3,
+    /// For globals that are defined in, and only used within, the executable.
+    LocalExec = 4,
+}
+impl From<LLVMThreadLocalMode> for ThreadLocalMode {
+    fn from(attr: LLVMThreadLocalMode) -> ThreadLocalMode {
+        unsafe { mem::transmute(attr) }
+    }
+}
+impl From<ThreadLocalMode> for LLVMThreadLocalMode {
+    fn from(attr: ThreadLocalMode) -> LLVMThreadLocalMode {
+        unsafe { mem::transmute(attr) }
+    }
+}
+
+/// The convention that a function uses to receive its arguments and return its result.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum CallConv {
+    /// The default C calling convention, which supports varargs.
+    C,
+    /// Attempts to make calls as fast as possible.
+    Fast,
+    /// Attempts to make the code in the caller as efficient as possible, assuming the call is rarely executed.
+    Cold,
+    /// The convention used by the Glasgow Haskell Compiler, which passes everything in registers.
+    GHC,
+    /// The convention used by the High-Performance Erlang compiler.
+    HiPE,
+    /// The convention used by WebKit's JavaScript JIT.
+    WebKitJS,
+    /// Dynamic calling convention for code patching stubs.
+    AnyReg,
+    /// Makes the callee preserve most registers, for rarely called code.
+    PreserveMost,
+    /// Makes the callee preserve all registers, for rarely called code.
+    PreserveAll,
+    /// The convention used by Swift.
+    Swift,
+    /// The convention used for access functions of C++ thread-local variables.
+    CxxFastTls,
+    /// Like `Fast`, but guarantees tail calls where possible without changing the caller.
+    Tail,
+    /// The stdcall convention used by the Win32 API on x86.
+    X86StdCall,
+    /// The fastcall convention on x86, which passes the first two arguments in registers.
+    X86FastCall,
+    /// The convention used for C++ member functions by MSVC on x86.
+    X86ThisCall,
+    /// The vectorcall convention on x86 and x86-64.
+    X86VectorCall,
+    /// The System V convention used on x86-64 by everything except Windows.
+    X8664SysV,
+    /// The convention used on x86-64 by Windows.
+    Win64,
+    /// The APCS convention on ARM.
+    ArmApcs,
+    /// The AAPCS convention on ARM.
+    ArmAapcs,
+    /// The AAPCS convention on ARM, with floating-point arguments in VFP registers.
+    ArmAapcsVfp,
+    /// A calling convention that this library doesn't know by name.
+    Other(u32),
+}
+impl From<c_uint> for CallConv {
+    fn from(conv: c_uint) -> CallConv {
+        match conv {
+            0 => CallConv::C,
+            8 => CallConv::Fast,
+            9 => CallConv::Cold,
+            10 => CallConv::GHC,
+            11 => CallConv::HiPE,
+            12 => CallConv::WebKitJS,
+            13 => CallConv::AnyReg,
+            14 => CallConv::PreserveMost,
+            15 => CallConv::PreserveAll,
+            16 => CallConv::Swift,
+            17 => CallConv::CxxFastTls,
+            18 => CallConv::Tail,
+            64 => CallConv::X86StdCall,
+            65 => CallConv::X86FastCall,
+            66 => CallConv::ArmApcs,
+            67 => CallConv::ArmAapcs,
+            68 => CallConv::ArmAapcsVfp,
+            70 => CallConv::X86ThisCall,
+            78 => CallConv::X8664SysV,
+            79 => CallConv::Win64,
+            80 => CallConv::X86VectorCall,
+            other => CallConv::Other(other),
+        }
+    }
+}
+impl From<CallConv> for c_uint {
+    fn from(conv: CallConv) -> c_uint {
+        match conv {
+            CallConv::C => 0,
+            CallConv::Fast => 8,
+            CallConv::Cold => 9,
+            CallConv::GHC => 10,
+            CallConv::HiPE => 11,
+            CallConv::WebKitJS => 12,
+            CallConv::AnyReg => 13,
+            CallConv::PreserveMost => 14,
+            CallConv::PreserveAll => 15,
+            CallConv::Swift => 16,
+            CallConv::CxxFastTls => 17,
+            CallConv::Tail => 18,
+            CallConv::X86StdCall => 64,
+            CallConv::X86FastCall => 65,
+            CallConv::ArmApcs => 66,
+            CallConv::ArmAapcs => 67,
+            CallConv::ArmAapcsVfp => 68,
+            CallConv::X86ThisCall => 70,
+            CallConv::X8664SysV => 78,
+            CallConv::Win64 => 79,
+            CallConv::X86VectorCall => 80,
+            CallConv::Other(other) => other,
+        }
+    }
+}
+
 impl From<LLVMLinkage> for Linkage {
     fn from(attr: LLVMLinkage) -> Linkage {
         unsafe { mem::transmute(attr) }
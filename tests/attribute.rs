@@ -0,0 +1,139 @@
+extern crate llvm_rs as llvm;
+use llvm::*;
+
+#[test]
+fn test_function_attributes() {
+    let ctx = Context::new();
+    let module = Module::new("attributes", &ctx);
+    let byte_ptr = PointerType::new(Type::get::<i8>(&ctx));
+    let i64_t = Type::get::<i64>(&ctx);
+    let pair_t = StructType::new(&ctx, &[i64_t, i64_t], false);
+    let sig = FunctionType::new(byte_ptr, &[byte_ptr, PointerType::new(pair_t)]);
+    let func = module.add_function("callee", sig);
+
+    let noinline = Attribute::new_enum(&ctx, "noinline");
+    let string = Attribute::new_string(&ctx, "llvm-rs", "yes");
+    func.add_attribute(AttributeIndex::Function, noinline);
+    func.add_attribute(AttributeIndex::Function, string);
+    func.add_attribute(AttributeIndex::Return, Attribute::new_int(&ctx, "dereferenceable", 16));
+    func.add_attribute(AttributeIndex::Param(0), Attribute::new_int(&ctx, "align", 8));
+    func.add_attributes(AttributeIndex::Param(0), &["nonnull"]);
+    func.add_attribute(AttributeIndex::Param(1), Attribute::new_type(&ctx, "byval", pair_t));
+    module.verify().unwrap();
+
+    assert!(noinline.is_enum() && noinline.is_kind("noinline"));
+    assert!(string.is_string() && !string.is_kind("noinline"));
+    assert_eq!(func.get_attributes(AttributeIndex::Function).len(), 2);
+    assert_eq!(func.get_attributes(AttributeIndex::Param(0)).len(), 2);
+    let align = func.get_enum_attribute(AttributeIndex::Param(0), "align").unwrap();
+    assert_eq!(align.get_int(), 8);
+    let deref = func.get_enum_attribute(AttributeIndex::Return, "dereferenceable").unwrap();
+    assert_eq!(deref.get_int(), 16);
+    let byval = func.get_enum_attribute(AttributeIndex::Param(1), "byval").unwrap();
+    assert!(byval.is_type() && byval.get_type() == Some(pair_t));
+    let string = func.get_string_attribute(AttributeIndex::Function, "llvm-rs").unwrap();
+    assert_eq!(string.get_string_key(), Some(Ok("llvm-rs")));
+    assert_eq!(string.get_string_value(), Some(Ok("yes")));
+    assert!(func.get_enum_attribute(AttributeIndex::Param(1), "nonnull").is_none());
+
+    let ir = module.to_string();
+    assert!(ir.contains(concat!(
+        "declare dereferenceable(16) i8* @callee(",
+        "i8* nonnull align 8, { i64, i64 }* byval({ i64, i64 })) #0",
+    )));
+    assert!(ir.contains("attributes #0 = { noinline \"llvm-rs\"=\"yes\" }"));
+
+    func.remove_enum_attribute(AttributeIndex::Function, "noinline");
+    func.remove_string_attribute(AttributeIndex::Function, "llvm-rs");
+    func.remove_enum_attribute(AttributeIndex::Return, "dereferenceable");
+    func.remove_enum_attribute(AttributeIndex::Param(0), "align");
+    func.remove_enum_attribute(AttributeIndex::Param(0), "nonnull");
+    func.remove_enum_attribute(AttributeIndex::Param(1), "byval");
+    module.verify().unwrap();
+    assert!(func.get_attributes(AttributeIndex::Function).is_empty());
+    assert!(module.to_string().contains("declare i8* @callee(i8*, { i64, i64 }*)\n"));
+}
+
+#[test]
+fn test_call_site_attributes() {
+    let ctx = Context::new();
+    let module = Module::new("attributes", &ctx);
+    let byte_ptr = PointerType::new(Type::get::<i8>(&ctx));
+    let i64_t = Type::get::<i64>(&ctx);
+    let pair_t = StructType::new(&ctx, &[i64_t, i64_t], false);
+    let sig = FunctionType::new(byte_ptr, &[byte_ptr, PointerType::new(pair_t)]);
+    let callee = module.add_function("callee", sig);
+    let func = module.add_function("caller", sig);
+    let builder = Builder::new(&ctx);
+    builder.position_at_end(func.append("entry"));
+    let call = builder.build_call(callee, &[&func[0], &func[1]]);
+    builder.build_ret(call);
+    let call = CallSite::from_super(call).unwrap();
+
+    call.add_attribute(AttributeIndex::Function, Attribute::new_enum(&ctx, "nounwind"));
+    call.add_attribute(AttributeIndex::Function, Attribute::new_string(&ctx, "llvm-rs", "yes"));
+    call.add_attribute(AttributeIndex::Return, Attribute::new_int(&ctx, "dereferenceable", 16));
+    call.add_attribute(AttributeIndex::Param(0), Attribute::new_int(&ctx, "align", 8));
+    call.add_attribute(AttributeIndex::Param(1), Attribute::new_type(&ctx, "byval", pair_t));
+    module.verify().unwrap();
+
+    assert_eq!(call.get_attributes(AttributeIndex::Function).len(), 2);
+    assert!(call.get_enum_attribute(AttributeIndex::Function, "nounwind").is_some());
+    let align = call.get_enum_attribute(AttributeIndex::Param(0), "align").unwrap();
+    assert_eq!(align.get_int(), 8);
+    let byval = call.get_enum_attribute(AttributeIndex::Param(1), "byval").unwrap();
+    assert!(byval.get_type() == Some(pair_t));
+    let string = call.get_string_attribute(AttributeIndex::Function, "llvm-rs").unwrap();
+    assert_eq!(string.get_string_value(), Some(Ok("yes")));
+
+    let ir = module.to_string();
+    assert!(ir.contains(concat!(
+        "call dereferenceable(16) i8* @callee(",
+        "i8* align 8 %0, { i64, i64 }* byval({ i64, i64 }) %1) #0",
+    )));
+    assert!(ir.contains("attributes #0 = { nounwind \"llvm-rs\"=\"yes\" }"));
+
+    call.remove_enum_attribute(AttributeIndex::Function, "nounwind");
+    call.remove_string_attribute(AttributeIndex::Function, "llvm-rs");
+    call.remove_enum_attribute(AttributeIndex::Return, "dereferenceable");
+    call.remove_enum_attribute(AttributeIndex::Param(0), "align");
+    call.remove_enum_attribute(AttributeIndex::Param(1), "byval");
+    module.verify().unwrap();
+    assert!(call.get_attributes(AttributeIndex::Param(1)).is_empty());
+    assert!(module.to_string().contains("call i8* @callee(i8* %0, { i64, i64 }* %1)\n"));
+}
+
+#[test]
+fn test_attribute_debug() {
+    let ctx = Context::new();
+    let pair_t = StructType::new(&ctx, &[Type::get::<i64>(&ctx)], false);
+    assert_eq!(format!("{:?}", Attribute::new_enum(&ctx, "noinline")), "noinline");
+    assert_eq!(format!("{:?}", Attribute::new_int(&ctx, "align", 8)), "align(8)");
+    let byval = Attribute::new_type(&ctx, "byval", pair_t);
+    assert_eq!(format!("{:?}", byval), format!("byval({:?})", pair_t));
+    let string = Attribute::new_string(&ctx, "key", "value");
+    assert_eq!(format!("{:?}", string), "\"key\"=\"value\"");
+}
+
+#[test]
+#[should_panic(expected = "noinline does not carry an integer")]
+fn test_int_attribute_kind() {
+    let ctx = Context::new();
+    Attribute::new_int(&ctx, "noinline", 1);
+}
+
+#[test]
+fn test_invalid_utf8_string_attribute() {
+    let ctx = Context::new();
+    let ir = "declare void @f() #0\nattributes #0 = { \"\\FF\"=\"x\" \"y\"=\"\\FE\" }\n";
+    let module = Module::parse_ir_from_str(&ctx, ir).unwrap();
+    let func = module.get_function("f").unwrap();
+    let attrs = func.get_attributes(AttributeIndex::Function);
+    assert_eq!(attrs.len(), 2);
+    let bad_key = attrs.iter().find(|attr| attr.get_string_value() == Some(Ok("x"))).unwrap();
+    let bad_value = func.get_string_attribute(AttributeIndex::Function, "y").unwrap();
+    assert!(bad_key.get_string_key().unwrap().is_err());
+    assert_eq!(bad_value.get_string_key(), Some(Ok("y")));
+    assert!(bad_value.get_string_value().unwrap().is_err());
+    assert_eq!(format!("{:?}", bad_key), "\"\u{FFFD}\"=\"x\"");
+}
@@ -0,0 +1,22 @@
+extern crate llvm_rs as llvm;
+use llvm::*;
+
+#[test]
+fn test_memory_buffer() {
+    let bytes = [0xde, 0xad, 0xbe, 0xef];
+    let copied = MemoryBuffer::new_from_bytes(&bytes, Some("copied"));
+    assert_eq!(copied.as_bytes(), &bytes);
+    assert!(copied.as_str().is_err());
+
+    let borrowed = MemoryBuffer::new_from_slice(b"text", None);
+    assert_eq!(borrowed.len(), 4);
+    assert_eq!(borrowed.as_str(), Ok("text"));
+
+    let ctx = Context::new();
+    let module = Module::new("buffer", &ctx);
+    module.add_global("answer", Type::get::<i32>(&ctx));
+    let buf = module.to_bitcode_buffer();
+    assert_eq!(&buf[..4], b"BC\xc0\xde");
+    let parsed = Module::parse_bitcode_from_buffer(&ctx, &buf).unwrap();
+    assert!(parsed.get_global("answer").is_some());
+}
@@ -0,0 +1,290 @@
+extern crate llvm_rs as llvm;
+use llvm::*;
+
+#[test]
+fn test_malloc_and_mem_intrinsics() {
+    let ctx = Context::new();
+    let module = Module::new("malloc", &ctx);
+    let i64_t = Type::get::<i64>(&ctx);
+    let func = module.add_function("llvm_rs_malloc", FunctionType::new(i64_t, &[i64_t]));
+    let builder = Builder::new(&ctx);
+    builder.position_at_end(func.append("entry"));
+    let len = 8u64.compile(&ctx);
+    let stack = builder.build_alloca(i64_t);
+    builder.build_store(&func[0], stack);
+    let array = builder.build_array_malloc(i64_t, 2u64.compile(&ctx));
+    builder.build_memcpy(array, stack, len, 8, false);
+    let copy = builder.build_malloc(i64_t);
+    builder.build_memmove(copy, array, len, 8, false);
+    let ones = builder.build_malloc(i64_t);
+    builder.build_memset(ones, 1u8.compile(&ctx), len, 8, false);
+    let sum = builder.build_add(builder.build_load(copy), builder.build_load(ones));
+    builder.build_free(array);
+    builder.build_free(copy);
+    builder.build_free(ones);
+    builder.build_ret(sum);
+    module.verify().unwrap();
+
+    let ir = module.to_string();
+    assert!(ir.contains("call i8* @malloc("));
+    assert!(ir.contains("call void @llvm.memcpy.p0i8.p0i8.i64("));
+    assert!(ir.contains("call void @llvm.memmove.p0i8.p0i8.i64("));
+    assert!(ir.contains("call void @llvm.memset.p0i8.i64("));
+    assert!(ir.contains("call void @free("));
+
+    let ee = JitEngine::new(&module, JitOptions::default()).unwrap();
+    ee.with_function(func, |f: extern "C" fn(i64) -> i64| {
+        assert_eq!(f(41), 41 + 0x0101_0101_0101_0101);
+    });
+    ee.remove_module(&module);
+}
+
+#[test]
+fn test_checked_arithmetic() {
+    let ctx = Context::new();
+    let module = Module::new("checked", &ctx);
+    let u64_t = Type::get::<u64>(&ctx);
+    let sig = FunctionType::new(Type::get::<u8>(&ctx), &[u64_t, u64_t]);
+    let builder = Builder::new(&ctx);
+    let ops: [(&str, for<'b> fn(&'b Builder, bool, &'b Value, &'b Value) -> &'b Value); 3] = [
+        ("add", |b, s, x, y| b.build_checked_add(s, x, y).1),
+        ("sub", |b, s, x, y| b.build_checked_sub(s, x, y).1),
+        ("mul", |b, s, x, y| b.build_checked_mul(s, x, y).1),
+    ];
+    let mut funcs = vec![];
+    for &(name, op) in &ops {
+        for &signed in &[true, false] {
+            let name = format!("llvm_rs_{}{}", if signed { "s" } else { "u" }, name);
+            let func = module.add_function(&name, sig);
+            builder.position_at_end(func.append("entry"));
+            let overflowed = op(&builder, signed, &func[0], &func[1]);
+            builder.build_ret(builder.build_zext(overflowed, Type::get::<u8>(&ctx)));
+            funcs.push(func);
+        }
+    }
+    module.verify().unwrap();
+
+    let (max, min) = (i64::max_value() as u64, i64::min_value() as u64);
+    let neg_one = -1i64 as u64;
+    let cases: [&[(u64, u64, u8)]; 6] = [
+        &[(max, 1, 1), (max, 0, 0), (min, neg_one, 1), (min, 0, 0)],
+        &[(u64::max_value(), 1, 1), (u64::max_value(), 0, 0), (max, 1, 0)],
+        &[(min, 1, 1), (min, 0, 0), (max, neg_one, 1), (max, 0, 0)],
+        &[(0, 1, 1), (1, 1, 0), (u64::max_value(), u64::max_value(), 0)],
+        &[(max, 2, 1), (min, neg_one, 1), (max, 1, 0), (min, 1, 0)],
+        &[(u64::max_value(), 2, 1), (1 << 32, 1 << 32, 1), (u64::max_value(), 1, 0)],
+    ];
+    let ee = JitEngine::new(&module, JitOptions::default()).unwrap();
+    for (func, cases) in funcs.into_iter().zip(cases.iter()) {
+        ee.with_function(func, |f: extern "C" fn((u64, u64)) -> u8| {
+            for &(a, b, overflowed) in cases.iter() {
+                assert_eq!(f((a, b)), overflowed, "{:?}({}, {})", func.get_name(), a, b);
+            }
+        });
+    }
+    ee.remove_module(&module);
+}
+
+#[test]
+fn test_trapping_arithmetic() {
+    let ctx = Context::new();
+    let module = Module::new("trapping", &ctx);
+    let func = module.add_function("trapping", Type::get::<fn(i32, i32) -> i32>(&ctx));
+    let builder = Builder::new(&ctx);
+    let entry = func.append("entry");
+    builder.position_at_end(entry);
+    let sum = builder.build_trapping_add(true, &func[0], &func[1]);
+    let diff = builder.build_trapping_sub(false, sum, &func[1]);
+    let product = builder.build_trapping_mul(true, diff, &func[0]);
+    builder.build_ret(product);
+    module.verify().unwrap();
+    assert!(builder.get_insert_block() != entry);
+
+    let ir = module.to_string();
+    for &(op, trap, cont) in &[("sadd", "trap", "cont"), ("usub", "trap1", "cont2"),
+                               ("smul", "trap3", "cont4")] {
+        assert!(ir.contains(&format!("call {{ i32, i1 }} @llvm.{}.with.overflow.i32(", op)));
+        assert!(ir.contains(&format!(", label %{}, label %{}\n", trap, cont)));
+        assert!(ir.contains(&format!("\n{}:", trap)));
+    }
+    assert_eq!(ir.matches("call void @llvm.trap()\n  unreachable\n").count(), 3);
+}
+
+#[test]
+fn test_gep() {
+    let ctx = Context::new();
+    let module = Module::new("gep", &ctx);
+    let i64_t = Type::get::<i64>(&ctx);
+    let pair_t = StructType::new(&ctx, &[Type::get::<i32>(&ctx), i64_t], false);
+    let func = module.add_function("llvm_rs_gep", FunctionType::new(i64_t, &[i64_t]));
+    let builder = Builder::new(&ctx);
+    builder.position_at_end(func.append("entry"));
+    let pair = builder.build_alloca(pair_t);
+    builder.build_store(&func[0], builder.build_struct_gep(pair, 1));
+    let field = builder.build_gep(pair, &[0i32.compile(&ctx), 1i32.compile(&ctx)]);
+    let value = builder.build_load(field);
+    let array = builder.build_array_alloca(i64_t, 4u64.compile(&ctx));
+    let third = builder.build_plain_gep(array, &[3i64.compile(&ctx)]);
+    let first = builder.build_gep(array, &[1i64.compile(&ctx)]);
+    let diff = builder.build_ptr_diff(third, first);
+    builder.build_ret(builder.build_add(value, diff));
+
+    let char_at = module.add_function("llvm_rs_char_at", Type::get::<fn(u64) -> u8>(&ctx));
+    builder.position_at_end(char_at.append("entry"));
+    let text = builder.build_global_string_ptr("hello");
+    assert_eq!(text.get_type(), PointerType::new(Type::get::<i8>(&ctx)));
+    builder.build_ret(builder.build_load(builder.build_gep(text, &[&char_at[0]])));
+    module.verify().unwrap();
+
+    let ir = module.to_string();
+    let field_gep = "getelementptr inbounds { i32, i64 }, { i32, i64 }* %1, i32 0, i32 1";
+    assert_eq!(ir.matches(field_gep).count(), 2);
+    assert!(ir.contains("getelementptr i64, i64* %"));
+    assert!(ir.contains("getelementptr inbounds i64, i64* %"));
+    assert!(ir.contains("c\"hello\\00\""));
+
+    let ee = JitEngine::new(&module, JitOptions::default()).unwrap();
+    ee.with_function(func, |f: extern "C" fn(i64) -> i64| assert_eq!(f(40), 42));
+    ee.with_function(char_at, |f: extern "C" fn(u64) -> u8| {
+        assert_eq!((0..6).map(|i| f(i)).collect::<Vec<_>>(), b"hello\0");
+    });
+    ee.remove_module(&module);
+}
+
+#[test]
+#[should_panic(expected = "field 2 is out of range")]
+fn test_struct_gep_out_of_range() {
+    let ctx = Context::new();
+    let module = Module::new("gep", &ctx);
+    let func = module.add_function("gep", Type::get::<fn()>(&ctx));
+    let builder = Builder::new(&ctx);
+    builder.position_at_end(func.append("entry"));
+    let i64_t = Type::get::<i64>(&ctx);
+    let pair = builder.build_alloca(StructType::new(&ctx, &[i64_t, i64_t], false));
+    builder.build_struct_gep(pair, 2);
+}
+
+#[test]
+#[should_panic(expected = "expected a pointer to a struct")]
+fn test_struct_gep_not_struct() {
+    let ctx = Context::new();
+    let module = Module::new("gep", &ctx);
+    let func = module.add_function("gep", Type::get::<fn()>(&ctx));
+    let builder = Builder::new(&ctx);
+    builder.position_at_end(func.append("entry"));
+    let value = builder.build_alloca(Type::get::<i64>(&ctx));
+    builder.build_struct_gep(value, 0);
+}
+
+#[test]
+#[should_panic(expected = "builder is not positioned in a function")]
+fn test_global_string_ptr_unpositioned() {
+    let ctx = Context::new();
+    Builder::new(&ctx).build_global_string_ptr("hello");
+}
+
+#[test]
+fn test_positioning_and_insert() {
+    let ctx = Context::new();
+    let module = Module::new("positioning", &ctx);
+    let func = module.add_function("positioning", Type::get::<fn(i64, i64) -> i64>(&ctx));
+    let entry = func.append("entry");
+    let builder = Builder::new(&ctx);
+    builder.position_at_end(entry);
+    let product = builder.build_named("product", |b| b.build_mul(&func[0], &func[1]));
+    let ret = Instruction::from_super(builder.build_ret(product)).unwrap();
+    assert!(ret.get_parent() == Some(entry));
+
+    builder.position_before(ret);
+    builder.build_named("sum", |b| b.build_add(product, &func[0]));
+    let product = Instruction::from_super(product).unwrap();
+    builder.position_at(entry, product);
+    builder.build_named("diff", |b| b.build_sub(&func[0], &func[1]));
+
+    builder.clear_insertion_position();
+    let mixed = Instruction::from_super(builder.build_xor(&func[0], &func[1])).unwrap();
+    assert!(mixed.get_parent().is_none());
+    builder.position_before(ret);
+    builder.insert_with_name(mixed, "mixed");
+    assert!(mixed.get_parent() == Some(entry));
+    assert_eq!(mixed.get_name(), Some("mixed"));
+    module.verify().unwrap();
+
+    let ir = module.to_string();
+    let lines = [
+        "%diff = sub i64 %0, %1",
+        "%product = mul i64 %0, %1",
+        "%sum = add i64 %product, %0",
+        "%mixed = xor i64 %0, %1",
+        "ret i64 %product",
+    ];
+    let positions: Vec<_> = lines.iter().map(|line| ir.find(line).expect(line)).collect();
+    let mut sorted = positions.clone();
+    sorted.sort();
+    assert_eq!(positions, sorted);
+}
+
+#[test]
+#[should_panic(expected = "is already in a block")]
+fn test_insert_parented() {
+    let ctx = Context::new();
+    let module = Module::new("insert", &ctx);
+    let func = module.add_function("insert", Type::get::<fn(i64) -> i64>(&ctx));
+    let builder = Builder::new(&ctx);
+    builder.position_at_end(func.append("entry"));
+    let sum = builder.build_add(&func[0], &func[0]);
+    builder.insert(Instruction::from_super(sum).unwrap());
+}
+
+#[test]
+fn test_call_conv_and_tail_calls() {
+    let ctx = Context::new();
+    let module = Module::new("calls", &ctx);
+    let callee = module.add_function("callee", Type::get::<fn(i64) -> i64>(&ctx));
+    assert_eq!(callee.get_call_conv(), CallConv::C);
+    callee.set_call_conv(CallConv::Other(8));
+    assert_eq!(callee.get_call_conv(), CallConv::Fast);
+    let func = module.add_function("caller", Type::get::<fn(i64) -> i64>(&ctx));
+    func.set_call_conv(CallConv::Other(100));
+    assert_eq!(func.get_call_conv(), CallConv::Other(100));
+    func.set_call_conv(CallConv::Tail);
+    assert_eq!(func.get_call_conv(), CallConv::Tail);
+
+    let builder = Builder::new(&ctx);
+    builder.position_at_end(func.append("entry"));
+    let call = CallSite::from_super(builder.build_call(callee, &[&func[0]])).unwrap();
+    assert_eq!(call.get_call_conv(), CallConv::Fast);
+    assert!(!call.is_tail_call());
+    call.set_call_conv(CallConv::Other(8));
+    call.set_tail_call(true);
+    assert!(call.is_tail_call());
+    call.set_tail_call(false);
+    assert!(!call.is_tail_call());
+    let tail = builder.build_tail_call(callee, &[call]);
+    let tail = CallSite::from_super(tail).unwrap();
+    assert!(tail.is_tail_call());
+    assert_eq!(tail.get_call_conv(), CallConv::Fast);
+    builder.build_ret(tail);
+    module.verify().unwrap();
+
+    let ir = module.to_string();
+    assert!(ir.contains("define tailcc i64 @caller(i64 %0)"));
+    assert!(ir.contains("  %1 = call fastcc i64 @callee(i64 %0)\n"));
+    assert!(ir.contains("  %2 = tail call fastcc i64 @callee(i64 %1)\n"));
+    assert!(ir.contains("declare fastcc i64 @callee(i64)"));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "call site convention doesn't match")]
+fn test_call_conv_mismatch() {
+    let ctx = Context::new();
+    let module = Module::new("calls", &ctx);
+    let callee = module.add_function("callee", Type::get::<fn()>(&ctx));
+    callee.set_call_conv(CallConv::Fast);
+    let func = module.add_function("caller", Type::get::<fn()>(&ctx));
+    let builder = Builder::new(&ctx);
+    builder.position_at_end(func.append("entry"));
+    let call = builder.build_call(callee, &[]);
+    CallSite::from_super(call).unwrap().set_call_conv(CallConv::Cold);
+}
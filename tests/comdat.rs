@@ -0,0 +1,45 @@
+extern crate llvm_rs as llvm;
+use llvm::*;
+use std::env;
+use std::fs;
+use std::process;
+
+#[test]
+fn test_comdat_group_section() {
+    let ctx = Context::new();
+    let module = Module::new("comdat", &ctx);
+    let comdat = module.get_or_insert_comdat("instance");
+    assert!(comdat == module.get_or_insert_comdat("instance"));
+    assert_eq!(comdat.get_selection_kind(), SelectionKind::Any);
+    comdat.set_selection_kind(SelectionKind::Largest);
+    assert_eq!(comdat.get_selection_kind(), SelectionKind::Largest);
+    comdat.set_selection_kind(SelectionKind::Any);
+
+    let global = module.add_global_variable("instance", 42u32.compile(&ctx));
+    assert!(global.get_comdat().is_none());
+    global.set_linkage(Linkage::LinkOnceODR);
+    global.set_comdat(comdat);
+    assert!(global.get_comdat() == Some(comdat));
+    module.verify().unwrap();
+
+    let path = env::temp_dir().join(format!("llvm-rs-comdat-{}.o", process::id()));
+    let path = path.to_str().unwrap();
+    let machine = TargetMachine::new().unwrap();
+    machine.emit_to_file(&module, path, FileType::Object).unwrap();
+    let object = ObjectFile::read(path).unwrap();
+    let groups = object.sections().filter(|s| s.name == ".group").count();
+    assert_eq!(groups, 1);
+    assert!(object.symbols().any(|s| s.name == "instance"));
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "is not a function or global variable")]
+fn test_alias_comdat() {
+    let ctx = Context::new();
+    let module = Module::new("comdat", &ctx);
+    let comdat = module.get_or_insert_comdat("instance");
+    let global = module.add_global_variable("instance", 42u32.compile(&ctx));
+    let alias = module.add_global_alias("alias", global);
+    alias.set_comdat(comdat);
+}
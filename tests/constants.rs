@@ -0,0 +1,168 @@
+extern crate llvm_rs as llvm;
+use llvm::*;
+
+#[test]
+fn test_constant_expressions() {
+    let ctx = Context::new();
+    let module = Module::new("constants", &ctx);
+    let i8_t = Type::get::<i8>(&ctx);
+    let i32_t = Type::get::<i32>(&ctx);
+    let i64_t = Type::get::<i64>(&ctx);
+    let (two, three) = (2i32.compile(&ctx), 3i32.compile(&ctx));
+
+    assert_eq!(Value::new_add(two, three), 5i32.compile(&ctx));
+    assert_eq!(Value::new_sub(two, three), (-1i32).compile(&ctx));
+    assert_eq!(Value::new_mul(two, three), 6i32.compile(&ctx));
+    assert_eq!(Value::new_div((-6i32).compile(&ctx), three), (-2i32).compile(&ctx));
+    assert_eq!(Value::new_udiv(7i32.compile(&ctx), two), three);
+    assert_eq!(Value::new_add(1.5f64.compile(&ctx), 1.0f64.compile(&ctx)), 2.5f64.compile(&ctx));
+    assert_eq!(Value::new_cmp(two, three, Predicate::LessThan), true.compile(&ctx));
+    assert_eq!(Value::new_select(false.compile(&ctx), two, three), three);
+    assert_eq!(Value::new_trunc(300i32.compile(&ctx), i8_t), 44i8.compile(&ctx));
+    assert_eq!(Value::new_sext((-1i8).compile(&ctx), i32_t), (-1i32).compile(&ctx));
+    assert_eq!(Value::new_zext((-1i8).compile(&ctx), i32_t), 255i32.compile(&ctx));
+
+    let pair = Value::new_struct(&ctx, &[two, three], false);
+    assert_eq!(Value::new_extract_value(pair, 1), three);
+    let swapped = Value::new_insert_value(Value::new_insert_value(pair, three, 0), two, 1);
+    assert_eq!(swapped, Value::new_struct(&ctx, &[three, two], false));
+
+    let array = ConstantArray::new(i32_t, &[two, three]);
+    assert_eq!(array.to_string(), "[2 x i32] [i32 2, i32 3]");
+    assert!(ConstantArray::from_super(ConstantArray::new(i32_t, &[])).is_none());
+
+    let global = module.add_global("array", array.get_type());
+    global.set_initializer(array);
+    let second = Value::new_gep(global, &[0i32.compile(&ctx), 1i32.compile(&ctx)]);
+    let address = Value::new_ptr_to_int(second, i64_t);
+    let back = Value::new_int_to_ptr(address, PointerType::new(i32_t));
+    assert_eq!(Value::new_bit_cast(back, PointerType::new(i8_t)).to_string(),
+               "i8* bitcast (i32* getelementptr inbounds ([2 x i32], [2 x i32]* @array, \
+                i32 0, i32 1) to i8*)");
+}
+
+#[test]
+#[should_panic(expected = "left == right")]
+fn test_constant_array_element_type() {
+    let ctx = Context::new();
+    ConstantArray::new(Type::get::<i32>(&ctx), &[1i32.compile(&ctx), 2i64.compile(&ctx)]);
+}
+
+#[test]
+#[should_panic(expected = "left == right")]
+fn test_constant_op_operand_types() {
+    let ctx = Context::new();
+    Value::new_add(1i32.compile(&ctx), 2i64.compile(&ctx));
+}
+
+#[test]
+#[should_panic(expected = "index 2 is out of range for")]
+fn test_extract_value_out_of_range() {
+    let ctx = Context::new();
+    let pair = Value::new_struct(&ctx, &[1i32.compile(&ctx), 2i32.compile(&ctx)], false);
+    Value::new_extract_value(pair, 2);
+}
+
+#[test]
+#[should_panic(expected = "index 3 is out of range for")]
+fn test_insert_value_out_of_range() {
+    let ctx = Context::new();
+    let i32_t = Type::get::<i32>(&ctx);
+    let array = ConstantArray::new(i32_t, &[1i32.compile(&ctx), 2i32.compile(&ctx)]);
+    Value::new_insert_value(array, 3i32.compile(&ctx), 3);
+}
+
+#[test]
+#[should_panic(expected = "expected a bool condition")]
+fn test_select_condition_type() {
+    let ctx = Context::new();
+    Value::new_select(1i32.compile(&ctx), 2i32.compile(&ctx), 3i32.compile(&ctx));
+}
+
+#[test]
+#[should_panic(expected = "expected an integer to a narrower integer")]
+fn test_trunc_widens() {
+    let ctx = Context::new();
+    Value::new_trunc(1i8.compile(&ctx), Type::get::<i32>(&ctx));
+}
+
+#[test]
+#[should_panic(expected = "expected an integer to a wider integer")]
+fn test_zext_narrows() {
+    let ctx = Context::new();
+    Value::new_zext(1i32.compile(&ctx), Type::get::<i8>(&ctx));
+}
+
+#[test]
+#[should_panic(expected = "expected an integer to a wider integer")]
+fn test_sext_float() {
+    let ctx = Context::new();
+    Value::new_sext(1.0f32.compile(&ctx), Type::get::<i64>(&ctx));
+}
+
+#[test]
+#[should_panic(expected = "expected a cast between types of the same size")]
+fn test_bit_cast_size() {
+    let ctx = Context::new();
+    Value::new_bit_cast(1i32.compile(&ctx), Type::get::<f64>(&ctx));
+}
+
+#[test]
+#[should_panic(expected = "expected a cast between types of the same size")]
+fn test_bit_cast_pointer() {
+    let ctx = Context::new();
+    let i64_t = Type::get::<i64>(&ctx);
+    Value::new_bit_cast(1i64.compile(&ctx), PointerType::new(i64_t));
+}
+
+#[test]
+#[should_panic(expected = "expected an integer to a pointer")]
+fn test_int_to_ptr_float() {
+    let ctx = Context::new();
+    let i64_t = Type::get::<i64>(&ctx);
+    Value::new_int_to_ptr(1.0f64.compile(&ctx), PointerType::new(i64_t));
+}
+
+#[test]
+#[should_panic(expected = "expected a pointer to an integer")]
+fn test_ptr_to_int_integer() {
+    let ctx = Context::new();
+    Value::new_ptr_to_int(1i32.compile(&ctx), Type::get::<i64>(&ctx));
+}
+
+#[test]
+#[should_panic(expected = "expected integers")]
+fn test_and_floats() {
+    let ctx = Context::new();
+    Value::new_and(1.0f32.compile(&ctx), 2.0f32.compile(&ctx));
+}
+
+#[test]
+#[should_panic(expected = "expected integers")]
+fn test_or_floats() {
+    let ctx = Context::new();
+    Value::new_or(1.0f64.compile(&ctx), 2.0f64.compile(&ctx));
+}
+
+#[test]
+#[should_panic(expected = "expected integers")]
+fn test_xor_floats() {
+    let ctx = Context::new();
+    Value::new_xor(1.0f32.compile(&ctx), 2.0f32.compile(&ctx));
+}
+
+#[test]
+#[should_panic(expected = "expected a pointer")]
+fn test_gep_non_pointer() {
+    let ctx = Context::new();
+    Value::new_gep(1i64.compile(&ctx), &[0i32.compile(&ctx)]);
+}
+
+#[test]
+#[should_panic(expected = "expected an integer index")]
+fn test_gep_index_type() {
+    let ctx = Context::new();
+    let module = Module::new("constants", &ctx);
+    let global = module.add_global("value", Type::get::<i32>(&ctx));
+    Value::new_in_bounds_gep(global, &[0.0f32.compile(&ctx)]);
+}
@@ -0,0 +1,52 @@
+extern crate llvm_rs as llvm;
+use llvm::*;
+use std::env;
+use std::fs;
+use std::process;
+
+#[test]
+fn test_debug_info_section() {
+    let ctx = Context::new();
+    let module = Module::new("debuginfo", &ctx);
+    let i32_t = Type::get::<i32>(&ctx);
+    let func = module.add_function("add_one", FunctionType::new(i32_t, &[i32_t]));
+    {
+        let dib = DebugInfoBuilder::new(&module);
+        let file = dib.create_file("add_one.c", "/tmp");
+        let unit = dib.create_compile_unit(SourceLanguage::C, file, "llvm-rs", false);
+        let int = dib.create_basic_type("int", 32, TypeEncoding::Signed);
+        let ty = dib.create_subroutine_type(file, Some(int), &[int]);
+        let scope = dib.create_function(func, unit, "add_one", file, 1, ty, false);
+        let block = dib.create_lexical_block(scope, file, 2, 1);
+        let x = dib.create_parameter_variable(scope, "x", 1, file, 1, int);
+        let member = dib.create_member_type(scope, "value", file, 3, 32, 32, 0, int);
+        let wrapper = dib.create_struct_type(scope, "wrapper", file, 3, 32, 32, &[member]);
+        let _ = dib.create_pointer_type(wrapper, 64, 64);
+        let _ = dib.create_auto_variable(block, "sum", file, 4, int);
+
+        let builder = Builder::new(&ctx);
+        let entry = func.append("entry");
+        builder.position_at_end(entry);
+        builder.set_current_debug_location(1, 1, scope);
+        let slot = builder.build_alloca(i32_t);
+        builder.build_store(&func[0], slot);
+        dib.insert_declare_at_end(slot, x, 1, 1, scope, entry);
+        builder.set_current_debug_location(4, 5, block);
+        let arg = builder.build_load(slot);
+        let sum = builder.build_add(arg, 1i32.compile(&ctx));
+        builder.build_ret(sum);
+        builder.clear_current_debug_location();
+    }
+    module.verify().unwrap();
+    assert!(format!("{:?}", module).contains("!DISubprogram(name: \"add_one\""));
+
+    let path = env::temp_dir().join(format!("llvm-rs-debuginfo-{}.o", process::id()));
+    let path = path.to_str().unwrap();
+    let machine = TargetMachine::new().unwrap();
+    machine.emit_to_file(&module, path, FileType::Object).unwrap();
+    let object = ObjectFile::read(path).unwrap();
+    let sections: Vec<_> = object.sections().map(|s| s.name).collect();
+    assert!(sections.contains(&".debug_info"));
+    assert!(sections.contains(&".debug_line"));
+    fs::remove_file(path).unwrap();
+}
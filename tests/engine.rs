@@ -0,0 +1,93 @@
+extern crate llvm_rs as llvm;
+use llvm::*;
+
+#[test]
+fn test_lazy_bitcode_module() {
+    let ctx = Context::new();
+    let bitcode = {
+        let module = Module::parse_ir_from_str(&ctx, r#"
+define i64 @llvm_rs_lazy_used(i64 %x) {
+  %1 = add i64 %x, 1
+  ret i64 %1
+}
+define i64 @llvm_rs_lazy_unused(i64 %x) {
+  ret i64 %x
+}
+"#).unwrap();
+        module.to_bitcode()
+    };
+    let buf = MemoryBuffer::new_from_bytes(b"not bitcode", None);
+    assert!(Module::get_lazy_bitcode_module(&ctx, buf).is_err());
+
+    let buf = MemoryBuffer::new_from_bytes(&bitcode, None);
+    let module = Module::get_lazy_bitcode_module(&ctx, buf).unwrap();
+    let used = module.get_function("llvm_rs_lazy_used").unwrap();
+    let unused = module.get_function("llvm_rs_lazy_unused").unwrap();
+    assert!(used.is_materializable() && unused.is_materializable());
+    used.materialize().unwrap();
+    assert!(!used.is_materializable() && unused.is_materializable());
+    let ir = module.to_string();
+    assert!(ir.contains("%1 = add i64 %x, 1"));
+    assert!(ir.contains("; Materializable\ndefine i64 @llvm_rs_lazy_unused(i64 %0) {}"));
+    module.materialize_all().unwrap();
+    assert!(!unused.is_materializable());
+    let ee = JitEngine::new(&module, JitOptions::default()).unwrap();
+    ee.with_function(used, |inc: extern "C" fn(i64) -> i64| assert_eq!(inc(41), 42));
+    ee.remove_module(&module);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn test_inline_asm() {
+    let ctx = Context::new();
+    let module = Module::new("asm", &ctx);
+    module.set_inline_asm(".globl llvm_rs_asm_seven");
+    module.append_inline_asm("llvm_rs_asm_seven:\n  movq $7, %rax\n  retq");
+    let asm = module.get_inline_asm().unwrap();
+    assert!(asm.starts_with(".globl llvm_rs_asm_seven\nllvm_rs_asm_seven:"));
+
+    let i64_t = Type::get::<i64>(&ctx);
+    let seven = module.add_function("llvm_rs_asm_seven", FunctionType::new(i64_t, &[]));
+    let func = module.add_function("llvm_rs_asm_add", FunctionType::new(i64_t, &[i64_t]));
+    let add_sig = FunctionType::new(i64_t, &[i64_t, i64_t]);
+    let add = InlineAsm::new(add_sig, "addq $2, $0", "=r,0,r", false, false, AsmDialect::Att);
+    let builder = Builder::new(&ctx);
+    builder.position_at_end(func.append("entry"));
+    let sum = builder.build_call(add, &[&func[0], builder.build_call(seven, &[])]);
+    builder.build_ret(sum);
+    module.verify().unwrap();
+
+    let ee = JitEngine::new(&module, JitOptions::default()).unwrap();
+    ee.with_function(func, |add_seven: extern "C" fn(i64) -> i64| assert_eq!(add_seven(35), 42));
+    ee.remove_module(&module);
+}
+
+#[test]
+fn test_global_ctors() {
+    let ctx = Context::new();
+    let module = Module::new("ctors", &ctx);
+    let i64_t = Type::get::<i64>(&ctx);
+    let counter = module.add_global("counter", i64_t);
+    counter.set_initializer(0i64.compile(&ctx));
+    let builder = Builder::new(&ctx);
+    let void_sig = FunctionType::new(Type::get::<()>(&ctx), &[]);
+    for digit in 1..3i64 {
+        let ctor = module.add_function(&format!("ctor{}", digit), void_sig);
+        builder.position_at_end(ctor.append("entry"));
+        let old = builder.build_load(counter);
+        let shifted = builder.build_mul(old, 10i64.compile(&ctx));
+        let new = builder.build_add(shifted, digit.compile(&ctx));
+        builder.build_store(new, counter);
+        builder.build_ret_void();
+        module.add_global_ctor(ctor, 3 - digit as u32);
+    }
+    module.add_global_dtor(module.get_function("ctor1").unwrap(), 0);
+    module.verify().unwrap();
+    assert!(module.get_global("llvm.global_ctors.1").is_none());
+    assert!(module.to_string().contains("@llvm.global_ctors = appending global [2 x"));
+
+    let ee = JitEngine::new(&module, JitOptions::default()).unwrap();
+    ee.run_static_constructors();
+    assert_eq!(unsafe { *ee.find_global::<i64>("counter").unwrap() }, 21);
+    ee.remove_module(&module);
+}
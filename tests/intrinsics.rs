@@ -0,0 +1,83 @@
+extern crate llvm_rs as llvm;
+use llvm::*;
+
+#[test]
+fn test_intrinsic_overloads() {
+    let ctx = Context::new();
+    let module = Module::new("intrinsics", &ctx);
+    let f64_t = Type::get::<f64>(&ctx);
+    let u32_t = Type::get::<u32>(&ctx);
+    let byte_ptr = PointerType::new(Type::get::<i8>(&ctx));
+
+    let sqrt = Intrinsic::Sqrt.declare(&module, &[f64_t]);
+    assert_eq!(sqrt.get_name(), Some("llvm.sqrt.f64"));
+    assert_eq!(sqrt, Intrinsic::Sqrt.declare(&module, &[f64_t]));
+    let add = Intrinsic::SAddWithOverflow.declare(&module, &[u32_t]);
+    assert_eq!(add.get_name(), Some("llvm.sadd.with.overflow.i32"));
+    let memcpy = Intrinsic::MemCpy.declare(&module, &[byte_ptr, byte_ptr, u32_t]);
+    assert_eq!(memcpy.get_name(), Some("llvm.memcpy.p0i8.p0i8.i32"));
+    let ctpop = Intrinsic::Ctpop.declare(&module, &[VectorType::new(u32_t, 4)]);
+    assert_eq!(ctpop.get_name(), Some("llvm.ctpop.v4i32"));
+    Intrinsic::Trap.declare(&module, &[]);
+
+    let func = module.add_function("hypot", Type::get::<fn(f64, f64) -> f64>(&ctx));
+    let builder = Builder::new(&ctx);
+    builder.position_at_end(func.append("entry"));
+    let a = builder.build_mul(&func[0], &func[0]);
+    let b = builder.build_mul(&func[1], &func[1]);
+    let sum = builder.build_add(a, b);
+    builder.build_ret(builder.build_call(sqrt, &[sum]));
+    module.verify().unwrap();
+}
+
+#[test]
+fn test_intrinsic_signatures() {
+    let ctx = Context::new();
+    let module = Module::new("intrinsics", &ctx);
+    let i32_t = Type::get::<i32>(&ctx);
+    let byte_ptr = PointerType::new(Type::get::<i8>(&ctx));
+    let lifetime_start = Intrinsic::LifetimeStart.declare(&module, &[byte_ptr]);
+    let lifetime_end = Intrinsic::LifetimeEnd.declare(&module, &[byte_ptr]);
+    let prefetch = Intrinsic::Prefetch.declare(&module, &[byte_ptr]);
+    let fshl = Intrinsic::Fshl.declare(&module, &[i32_t]);
+    let fshr = Intrinsic::Fshr.declare(&module, &[i32_t]);
+    let expect = Intrinsic::Expect.declare(&module, &[i32_t]);
+    let stack_save = Intrinsic::StackSave.declare(&module, &[]);
+    let stack_restore = Intrinsic::StackRestore.declare(&module, &[]);
+    let ctlz = Intrinsic::Ctlz.declare(&module, &[i32_t]);
+    let cttz = Intrinsic::Cttz.declare(&module, &[i32_t]);
+    let names = [
+        (lifetime_start, "llvm.lifetime.start.p0i8"),
+        (lifetime_end, "llvm.lifetime.end.p0i8"),
+        (prefetch, "llvm.prefetch.p0i8"),
+        (fshl, "llvm.fshl.i32"),
+        (fshr, "llvm.fshr.i32"),
+        (expect, "llvm.expect.i32"),
+        (stack_save, "llvm.stacksave"),
+        (stack_restore, "llvm.stackrestore"),
+        (ctlz, "llvm.ctlz.i32"),
+        (cttz, "llvm.cttz.i32"),
+    ];
+    for &(func, name) in &names {
+        assert_eq!(func.get_name(), Some(name));
+    }
+
+    let func = module.add_function("bits", FunctionType::new(i32_t, &[i32_t]));
+    let builder = Builder::new(&ctx);
+    builder.position_at_end(func.append("entry"));
+    let x = &func[0];
+    let stack = builder.build_call(stack_save, &[]);
+    let slot = builder.build_bit_cast(builder.build_alloca(i32_t), byte_ptr);
+    builder.build_call(lifetime_start, &[4i64.compile(&ctx), slot]);
+    let (zero, one, three) = (0i32.compile(&ctx), 1i32.compile(&ctx), 3i32.compile(&ctx));
+    builder.build_call(prefetch, &[slot, zero, three, one]);
+    let rotated = builder.build_call(fshl, &[x, x, three]);
+    let rotated = builder.build_call(fshr, &[rotated, rotated, three]);
+    let expected = builder.build_call(expect, &[rotated, zero]);
+    let leading = builder.build_call(ctlz, &[expected, false.compile(&ctx)]);
+    let trailing = builder.build_call(cttz, &[expected, true.compile(&ctx)]);
+    builder.build_call(lifetime_end, &[4i64.compile(&ctx), slot]);
+    builder.build_call(stack_restore, &[stack]);
+    builder.build_ret(builder.build_add(leading, trailing));
+    module.verify().unwrap();
+}
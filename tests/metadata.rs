@@ -0,0 +1,52 @@
+extern crate llvm_rs as llvm;
+use llvm::*;
+
+#[test]
+fn test_instruction_and_named_metadata() {
+    let ctx = Context::new();
+    let module = Module::new("metadata", &ctx);
+    let i32_t = Type::get::<i32>(&ctx);
+    let func = module.add_function("load", FunctionType::new(i32_t, &[PointerType::new(i32_t)]));
+    let builder = Builder::new(&ctx);
+    builder.position_at_end(func.append("entry"));
+    let load = builder.build_load(&func[0]);
+    builder.build_ret(load);
+
+    let range_kind = ctx.get_md_kind_id("range");
+    let custom_kind = ctx.get_md_kind_id("llvm-rs.custom");
+    assert_eq!(range_kind, ctx.get_md_kind_id("range"));
+    assert!(range_kind != custom_kind);
+
+    let range = MDNode::new(&ctx, &[0i32.compile(&ctx), 10i32.compile(&ctx)]);
+    let note = MDString::new(&ctx, "checked");
+    assert_eq!(note.get_string(), Ok("checked"));
+    let custom = MDNode::new(&ctx, &[note]);
+    let load = Instruction::from_super(load).unwrap();
+    load.set_metadata(range_kind, range);
+    load.set_metadata(custom_kind, custom);
+    assert!(load.get_metadata(range_kind) == Some(range));
+    let operands = load.get_metadata(custom_kind).unwrap().get_operands();
+    let text = MDString::from_super(operands[0].unwrap()).unwrap();
+    assert_eq!(text.get_string(), Ok("checked"));
+    load.remove_metadata(custom_kind);
+    assert!(load.get_metadata(custom_kind).is_none());
+
+    assert!(module.get_named_metadata("llvm.ident").is_empty());
+    let ident = MDNode::new(&ctx, &[MDString::new(&ctx, "llvm-rs")]);
+    module.add_named_metadata_operand("llvm.ident", ident);
+    assert!(module.get_named_metadata("llvm.ident") == [ident]);
+    module.verify().unwrap();
+    assert!(format!("{:?}", module).contains("!range !1"));
+}
+
+#[test]
+fn test_invalid_utf8_metadata() {
+    let ctx = Context::new();
+    let ir = "!\\FF = !{!0}\n!strings = !{!0}\n!0 = !{!\"\\FF\"}\n";
+    let module = Module::parse_ir_from_str(&ctx, ir).unwrap();
+    let names: Vec<_> = module.named_metadata().map(|m| m.get_name().ok()).collect();
+    assert_eq!(names, [None, Some("strings")]);
+    let node = module.get_named_metadata("strings")[0];
+    let text = MDString::from_super(node.get_operands()[0].unwrap()).unwrap();
+    assert!(text.get_string().is_err());
+}
@@ -0,0 +1,296 @@
+extern crate llvm_rs as llvm;
+use llvm::*;
+use std::{env, fs, process};
+
+const IR: &'static str = r#"
+%pair = type { i32, %inner* }
+%inner = type { double }
+%local = type { i8 }
+%unused = type { i16 }
+
+@first = global i32 1
+@second = global %pair zeroinitializer
+@alias = alias i32, i32* @first
+
+define void @use_local() {
+  %1 = alloca %local
+  ret void
+}
+
+!llvm.ident = !{!0}
+!0 = !{!"llvm-rs"}
+"#;
+
+#[test]
+fn test_module_iterators() {
+    let ctx = Context::new();
+    let module = Module::parse_ir_from_str(&ctx, IR).unwrap();
+
+    let globals: Vec<_> = module.globals().map(|g| g.get_name().unwrap()).collect();
+    assert_eq!(globals, ["first", "second"]);
+    let aliases: Vec<_> = module.aliases().map(|a| a.get_name().unwrap()).collect();
+    assert_eq!(aliases, ["alias"]);
+    let functions: Vec<_> = module.into_iter().map(|f| f.get_name().unwrap()).collect();
+    assert_eq!(functions, ["use_local"]);
+    let metadata: Vec<_> = module.named_metadata().map(|m| m.get_name().unwrap()).collect();
+    assert_eq!(metadata, ["llvm.ident"]);
+    let structs: Vec<_> = module.struct_types().map(|s| s.get_name().unwrap()).collect();
+    assert_eq!(structs, ["pair", "inner", "local"]);
+}
+
+#[test]
+fn test_remove_globals() {
+    let ctx = Context::new();
+    let module = Module::parse_ir_from_str(&ctx, IR).unwrap();
+    let first = module.get_global("first").unwrap();
+    let second = module.get_global("second").unwrap();
+    let alias = module.aliases().next().unwrap();
+    assert!(alias.get_aliasee() == &**first);
+
+    assert_eq!(&*module.remove_global(first).unwrap_err(), "value still has uses");
+    let err = module.remove_global(alias).unwrap_err();
+    assert_eq!(&*err, "global is not a function or global variable");
+    let other = Module::new("other", &ctx);
+    let foreign = other.add_global_variable("foreign", 4u32.compile(&ctx));
+    let err = module.remove_global(foreign).unwrap_err();
+    assert_eq!(&*err, "global belongs to another module");
+    let third = module.add_global_variable("third", 3u32.compile(&ctx));
+    alias.set_aliasee(third);
+    assert!(alias.get_aliasee() == &***third);
+    module.remove_global(first).unwrap();
+    module.remove_global(second).unwrap();
+    let globals: Vec<_> = module.globals().map(|g| g.get_name().unwrap()).collect();
+    assert_eq!(globals, ["third"]);
+    module.verify().unwrap();
+}
+
+#[test]
+fn test_module_flags_and_ident() {
+    let ctx = Context::new();
+    let module = Module::new("flags", &ctx);
+    assert!(module.get_flag("wchar_size").is_none());
+    module.add_flag(ModuleFlagBehavior::Error, "wchar_size", 4u32.compile(&ctx));
+    module.add_flag(ModuleFlagBehavior::Warning, "Dwarf Version", 4u32.compile(&ctx));
+    assert!(module.get_flag("wchar_size") == Some(4u32.compile(&ctx)));
+
+    module.add_ident("llvm-rs 0.1");
+    module.add_ident("other tool");
+    assert_eq!(module.get_idents().unwrap(), ["llvm-rs 0.1", "other tool"]);
+    module.verify().unwrap();
+    let ir = format!("{:?}", module);
+    assert!(ir.contains("!{i32 1, !\"wchar_size\", i32 4}"));
+    assert!(ir.contains("!{i32 2, !\"Dwarf Version\", i32 4}"));
+}
+
+#[test]
+fn test_bitcode_round_trip() {
+    let ctx = Context::new();
+    let module = Module::parse_ir_from_str(&ctx, IR).unwrap();
+    let bitcode = module.to_bitcode();
+    let mut written = Vec::new();
+    module.write_bitcode_to(&mut written).unwrap();
+    assert_eq!(bitcode, written);
+
+    let parsed = Module::parse_bitcode_from_bytes(&ctx, &bitcode).unwrap();
+    assert!(parsed.get_global("second").is_some());
+    assert!(Module::parse_bitcode_from_bytes(&ctx, b"not bitcode").is_err());
+    assert!(Module::parse_bitcode(&ctx, "does/not/exist.bc").is_err());
+}
+
+#[test]
+fn test_print_ir() {
+    let ctx = Context::new();
+    let module = Module::parse_ir_from_str(&ctx, IR).unwrap();
+    let text = module.to_string();
+    assert!(text.contains("@first = global i32 1"));
+    assert_eq!(text, format!("{:?}", module));
+    let mut written = Vec::new();
+    module.write_ir(&mut written).unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), text);
+
+    let func = module.get_function("use_local").unwrap();
+    assert!(func.to_string().starts_with("define void @use_local()"));
+    assert_eq!(func.get_type().to_string(), "void ()*");
+    assert_eq!(module.get_global("first").unwrap().to_string(), "@first = global i32 1");
+
+    let path = env::temp_dir().join(format!("llvm-rs-print-{}.ll", process::id()));
+    module.print_to_file(path.to_str().unwrap()).unwrap();
+    let printed = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(printed, text);
+    assert!(module.print_to_file("does/not/exist.ll").is_err());
+}
+
+#[test]
+fn test_global_properties() {
+    let ctx = Context::new();
+    let module = Module::parse_ir_from_str(&ctx, IR).unwrap();
+    let first = GlobalVariable::from_super(module.get_global("first").unwrap()).unwrap();
+    let func = module.get_function("use_local").unwrap();
+    let alias = module.aliases().next().unwrap();
+
+    assert_eq!(first.get_visibility(), Visibility::Default);
+    first.set_visibility(Visibility::Hidden);
+    assert_eq!(first.get_visibility(), Visibility::Hidden);
+    alias.set_visibility(Visibility::Protected);
+    assert_eq!(alias.get_visibility(), Visibility::Protected);
+    assert_eq!(func.get_dll_storage_class(), DLLStorageClass::Default);
+    func.set_dll_storage_class(DLLStorageClass::DLLExport);
+    assert_eq!(func.get_dll_storage_class(), DLLStorageClass::DLLExport);
+
+    assert_eq!(first.get_section(), None);
+    first.set_section("llvm_rs_data");
+    assert_eq!(first.get_section(), Some("llvm_rs_data"));
+    assert_eq!(alias.get_section(), Some("llvm_rs_data"));
+    func.set_section("llvm_rs_text");
+    assert_eq!(func.get_section(), Some("llvm_rs_text"));
+    assert_eq!(first.get_alignment(), 0);
+    first.set_alignment(16);
+    assert_eq!(first.get_alignment(), 16);
+    func.set_alignment(32);
+    assert_eq!(func.get_alignment(), 32);
+
+    assert_eq!(first.get_unnamed_addr(), UnnamedAddr::None);
+    first.set_unnamed_addr(UnnamedAddr::Local);
+    assert_eq!(first.get_unnamed_addr(), UnnamedAddr::Local);
+    func.set_unnamed_addr(UnnamedAddr::Global);
+    assert_eq!(func.get_unnamed_addr(), UnnamedAddr::Global);
+
+    assert_eq!(first.get_thread_local_mode(), ThreadLocalMode::NotThreadLocal);
+    assert!(!first.is_thread_local());
+    first.set_thread_local_mode(ThreadLocalMode::InitialExec);
+    assert_eq!(first.get_thread_local_mode(), ThreadLocalMode::InitialExec);
+    assert!(first.is_thread_local());
+    module.verify().unwrap();
+
+    let ir = module.to_string();
+    assert!(ir.contains(concat!(
+        "@first = hidden thread_local(initialexec) local_unnamed_addr global i32 1, ",
+        "section \"llvm_rs_data\", align 16",
+    )));
+    assert!(ir.contains("@alias = protected alias i32, i32* @first"));
+    assert!(ir.contains(
+        "define dllexport void @use_local() unnamed_addr section \"llvm_rs_text\" align 32 {"
+    ));
+}
+
+#[test]
+#[should_panic(expected = "is not a function or global variable")]
+fn test_alias_section() {
+    let ctx = Context::new();
+    let module = Module::parse_ir_from_str(&ctx, IR).unwrap();
+    module.aliases().next().unwrap().set_section("llvm_rs_data");
+}
+
+#[test]
+#[should_panic(expected = "is not a function or global variable")]
+fn test_alias_alignment() {
+    let ctx = Context::new();
+    let module = Module::parse_ir_from_str(&ctx, IR).unwrap();
+    module.aliases().next().unwrap().set_alignment(8);
+}
+
+#[test]
+fn test_invalid_utf8_inline_asm() {
+    let ctx = Context::new();
+    let module = Module::parse_ir_from_str(&ctx, "module asm \"nop \\FF\"").unwrap();
+    assert!(module.get_inline_asm().is_err());
+    module.set_inline_asm("nop");
+    let old = module.get_inline_asm().unwrap();
+    module.append_inline_asm("ret");
+    assert_eq!(old, "nop\n");
+    assert_eq!(module.get_inline_asm().unwrap(), "nop\nret\n");
+}
+
+#[test]
+fn test_legacy_global_ctors() {
+    let ctx = Context::new();
+    let module = Module::parse_ir_from_str(&ctx, concat!(
+        "@llvm.global_ctors = appending global [1 x { i32, void ()* }] ",
+        "[{ i32, void ()* } { i32 1, void ()* @old }]\n",
+        "define void @old() {\n  ret void\n}\n",
+    )).unwrap();
+    let void_sig = FunctionType::new(Type::get::<()>(&ctx), &[]);
+    let new = module.add_function("new", void_sig);
+    let builder = Builder::new(&ctx);
+    builder.position_at_end(new.append("entry"));
+    builder.build_ret_void();
+    module.add_global_ctor(new, 0);
+    module.verify().unwrap();
+    assert!(module.to_string().contains(concat!(
+        "[{ i32, void ()*, i8* } { i32 0, void ()* @new, i8* null }, ",
+        "{ i32, void ()*, i8* } { i32 1, void ()* @old, i8* null }]",
+    )));
+}
+
+#[test]
+fn test_zeroed_global_ctors() {
+    let ctx = Context::new();
+    let void_sig = FunctionType::new(Type::get::<()>(&ctx), &[]);
+    for ir in &[
+        "@llvm.global_ctors = appending global [1 x { i32, void ()*, i8* }] zeroinitializer\n",
+        concat!(
+            "@llvm.global_ctors = appending global [1 x { i32, void ()*, i8* }] ",
+            "[{ i32, void ()*, i8* } zeroinitializer]\n",
+        ),
+    ] {
+        let module = Module::parse_ir_from_str(&ctx, ir).unwrap();
+        let new = module.add_function("new", void_sig);
+        let builder = Builder::new(&ctx);
+        builder.position_at_end(new.append("entry"));
+        builder.build_ret_void();
+        module.add_global_ctor(new, 1);
+        module.verify().unwrap();
+        assert!(module.to_string().contains(concat!(
+            "[{ i32, void ()*, i8* } zeroinitializer, ",
+            "{ i32, void ()*, i8* } { i32 1, void ()* @new, i8* null }]",
+        )));
+    }
+}
+
+#[test]
+fn test_global_ctors_keep_references() {
+    let ctx = Context::new();
+    let module = Module::new("ctors", &ctx);
+    let void_sig = FunctionType::new(Type::get::<()>(&ctx), &[]);
+    let builder = Builder::new(&ctx);
+    for name in &["first", "second"] {
+        let func = module.add_function(name, void_sig);
+        builder.position_at_end(func.append("entry"));
+        builder.build_ret_void();
+    }
+    module.add_global_ctor(module.get_function("first").unwrap(), 0);
+    let old = GlobalVariable::from_super(module.get_global("llvm.global_ctors").unwrap()).unwrap();
+    module.add_global_ctor(module.get_function("second").unwrap(), 0);
+    module.verify().unwrap();
+    assert!(old.get_initializer().is_none());
+    assert!(!old.has_uses());
+    assert!(old.delete().is_err());
+    let new = module.get_global("llvm.global_ctors").unwrap();
+    assert_eq!(new.get_name(), Some("llvm.global_ctors"));
+    assert!(module.to_string().contains("@llvm.global_ctors = appending global [2 x"));
+}
+
+#[test]
+#[should_panic(expected = "must take no arguments and return nothing")]
+fn test_global_ctor_signature() {
+    let ctx = Context::new();
+    let module = Module::new("ctors", &ctx);
+    let i64_t = Type::get::<i64>(&ctx);
+    let func = module.add_function("ctor", FunctionType::new(i64_t, &[i64_t]));
+    module.add_global_ctor(func, 0);
+}
+
+#[test]
+fn test_invalid_utf8_ident_and_flag() {
+    let ctx = Context::new();
+    let module = Module::parse_ir_from_str(&ctx, concat!(
+        "!llvm.ident = !{!0}\n",
+        "!llvm.module.flags = !{!1}\n",
+        "!0 = !{!\"\\FF\"}\n",
+        "!1 = !{i32 1, !\"producer\", !\"\\FE\"}\n",
+    )).unwrap();
+    assert!(module.get_idents().is_err());
+    let producer = MDString::from_super(module.get_flag("producer").unwrap()).unwrap();
+    assert!(producer.get_string().is_err());
+}
@@ -0,0 +1,55 @@
+extern crate llvm_rs as llvm;
+use llvm::*;
+use std::fs;
+use std::process;
+
+// This is the only test in this file because `write_perf_map` must not run while another
+// thread uses a `JitEngine`.
+#[test]
+fn test_perf_map() {
+    let ctx = Context::new();
+    let i64_t = Type::get::<i64>(&ctx);
+    let builder = Builder::new(&ctx);
+    let mut addresses = Vec::new();
+    let names = ["llvm_rs_perf_double", "llvm_rs_perf_hidden"];
+    let modules: Vec<_> = names
+        .iter()
+        .map(|name| {
+            let module = Module::new(name, &ctx);
+            let func = module.add_function(name, FunctionType::new(i64_t, &[i64_t]));
+            builder.position_at_end(func.append("entry"));
+            builder.build_ret(builder.build_mul(&func[0], 2i64.compile(&ctx)));
+            module.verify().unwrap();
+            module
+        })
+        .collect();
+    let mut engines = Vec::new();
+    for ((module, name), &gdb_registration) in modules.iter().zip(&names).zip(&[true, false]) {
+        let options = JitOptions {
+            gdb_registration: gdb_registration,
+            ..JitOptions::default()
+        };
+        let ee = JitEngine::new(module, options).unwrap();
+        let func = module.get_function(name).unwrap();
+        ee.with_function(func, |double: extern "C" fn(i64) -> i64| {
+            assert_eq!(double(21), 42);
+            addresses.push(double as usize as u64);
+        });
+        engines.push(ee);
+    }
+    unsafe { JitEngine::write_perf_map().unwrap() };
+    for (ee, module) in engines.iter().zip(&modules) {
+        ee.remove_module(module);
+    }
+
+    let path = format!("/tmp/perf-{}.map", process::id());
+    let map = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    let line = map.lines()
+        .find(|line| line.ends_with(" llvm_rs_perf_double"))
+        .expect("function missing from perf map");
+    let fields: Vec<_> = line.split(' ').collect();
+    assert_eq!(u64::from_str_radix(fields[0], 16).unwrap(), addresses[0]);
+    assert!(u64::from_str_radix(fields[1], 16).unwrap() > 0);
+    assert!(!map.contains("llvm_rs_perf_hidden"));
+}
@@ -1,5 +1,6 @@
 extern crate llvm_rs as llvm;
 use llvm::*;
+use std::{env, fs, process};
 
 #[test]
 fn test_ir_reader() {
@@ -13,3 +14,36 @@ fn test_ir_reader() {
         module.err().unwrap()
     );
 }
+
+#[test]
+fn test_ir_reader_errors() {
+    let ctx = Context::new();
+    let err = Module::parse_ir_from_str(&ctx, "define i32 @main() {\n  ret i32 %x\n}")
+        .err()
+        .unwrap();
+    assert_eq!(err.file, "<string>");
+    assert_eq!(err.line, Some(2));
+    assert_eq!(err.column, Some(11));
+    assert_eq!(err.message, "use of undefined value '%x'");
+    assert_eq!(err.source_line.as_ref().unwrap(), "  ret i32 %x");
+    assert!(err.to_string().starts_with("<string>:2:11: error: "));
+
+    let err = Module::parse_ir_from_file(&ctx, "does/not/exist.ll").err().unwrap();
+    assert_eq!(err.file, "does/not/exist.ll");
+    assert_eq!(err.line, None);
+}
+
+#[test]
+fn test_ir_reader_file() {
+    let ctx = Context::new();
+    let path = env::temp_dir().join(format!("llvm-rs-reader-{}.ll", process::id()));
+    fs::write(&path, "define i32 @main() { ret i32 42 }\n@bad = global i32 x\n").unwrap();
+    let err = Module::parse_ir_from_file(&ctx, path.to_str().unwrap()).err().unwrap();
+    assert_eq!(err.file, path.to_str().unwrap());
+    assert_eq!((err.line, err.column), (Some(2), Some(19)));
+
+    fs::write(&path, "define i32 @main() { ret i32 42 }\n").unwrap();
+    let module = Module::parse_ir_from_file(&ctx, path.to_str().unwrap());
+    fs::remove_file(&path).unwrap();
+    assert!(module.unwrap().get_function("main").is_some());
+}
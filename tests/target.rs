@@ -0,0 +1,51 @@
+extern crate llvm_rs as llvm;
+use llvm::*;
+
+#[test]
+fn test_module_triple_and_layout() {
+    let triple = Triple::new("x86_64-linux-gnu");
+    assert_eq!(triple.arch, "x86_64");
+    assert_eq!(triple.vendor, "unknown");
+    assert_eq!(triple.os, "linux");
+    assert_eq!(triple.environment, "gnu");
+    assert_eq!(triple.to_string(), "x86_64-unknown-linux-gnu");
+    assert_eq!(Triple::new("aarch64-apple-darwin").environment, "");
+
+    let ctx = Context::new();
+    let module = Module::new("target", &ctx);
+    module.set_triple(&triple);
+    assert_eq!(module.get_target(), "x86_64-unknown-linux-gnu");
+    assert_eq!(module.get_triple(), triple);
+
+    let machine = TargetMachine::new().unwrap();
+    module.configure_for(&machine);
+    assert_eq!(module.get_triple(), Triple::host());
+    assert_eq!(module.get_data_layout(), machine.target_data().to_string());
+}
+
+#[test]
+fn test_target_data_layout() {
+    let ctx = Context::new();
+    let module = Module::new("layout", &ctx);
+    module.set_data_layout(&TargetData::from_string("e-p:32:32-i64:64"));
+    let old = module.get_data_layout();
+    module.set_data_layout(&TargetData::from_string("e-p:64:64"));
+    assert_eq!(old, "e-p:32:32-i64:64");
+    assert_eq!(module.get_data_layout(), "e-p:64:64");
+    module.set_data_layout(&TargetData::from_string(&old));
+    let data = TargetData::from_module(&module);
+    assert_eq!(data.to_string(), "e-p:32:32-i64:64");
+    assert_eq!(data.get_pointer_size(), 4);
+    assert_eq!(data.get_pointer_size_in(AddressSpace::Generic), 4);
+    assert_eq!(data.get_int_ptr_type(&ctx), Type::get::<i32>(&ctx));
+
+    let i8_t = Type::get::<i8>(&ctx);
+    let i64_t = Type::get::<i64>(&ctx);
+    let pair = StructType::new(&ctx, &[i8_t, i64_t, i8_t], false);
+    let layout = data.struct_layout(pair).unwrap();
+    assert_eq!((layout.size, layout.alignment), (24, 8));
+    let offsets: Vec<_> = layout.fields.iter().map(|field| field.offset).collect();
+    assert_eq!(offsets, [0, 8, 16]);
+    assert_eq!(layout.fields[1].size, 8);
+    assert!(data.struct_layout(StructType::new_opaque(&ctx, "opaque")).is_none());
+}
@@ -0,0 +1,47 @@
+// Functions that LLVM's C API is missing, written against the C++ API.
+#include <llvm-c/Core.h>
+#include <llvm-c/ExecutionEngine.h>
+#include <llvm/ExecutionEngine/ExecutionEngine.h>
+#include <llvm/ExecutionEngine/JITEventListener.h>
+#include <llvm/IR/GlobalValue.h>
+#include <llvm/IR/GlobalVariable.h>
+#include <llvm/IR/Module.h>
+#include <llvm/Support/Error.h>
+
+using namespace llvm;
+
+extern "C" {
+
+// MCJIT always registers the objects it emits with GDB's JIT interface, so this removes the
+// listener that does that again.
+void LLVMRsUnregisterGDBListener(LLVMExecutionEngineRef EE) {
+    unwrap(EE)->UnregisterJITEventListener(JITEventListener::createGDBRegistrationListener());
+}
+
+// Returns the message of `Err` through `OutMessage` if it is an error.
+static LLVMBool errorToMessage(Error Err, char **OutMessage) {
+    if (Err) {
+        *OutMessage = LLVMCreateMessage(toString(std::move(Err)).c_str());
+        return 1;
+    }
+    return 0;
+}
+
+LLVMBool LLVMRsIsMaterializable(LLVMValueRef GV) {
+    return unwrap<GlobalValue>(GV)->isMaterializable();
+}
+
+LLVMBool LLVMRsMaterialize(LLVMValueRef GV, char **OutMessage) {
+    return errorToMessage(unwrap<GlobalValue>(GV)->materialize(), OutMessage);
+}
+
+LLVMBool LLVMRsMaterializeAll(LLVMModuleRef M, char **OutMessage) {
+    return errorToMessage(unwrap(M)->materializeAll(), OutMessage);
+}
+
+// Unlinks `GV` from its module without freeing it, unlike `LLVMDeleteGlobal`.
+void LLVMRsRemoveGlobalFromParent(LLVMValueRef GV) {
+    unwrap<GlobalVariable>(GV)->removeFromParent();
+}
+
+}